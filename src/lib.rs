@@ -0,0 +1,2 @@
+pub mod mix;
+pub mod mixal;
@@ -1,9 +1,5 @@
-mod mix;
-mod mixal;
-
-use mix::machine::Mix;
-use mixal::lexer::Lexer;
-use mixal::parser::Parser;
+use mix_vm::mixal::lexer::Lexer;
+use mix_vm::mixal::parser::Parser;
 
 fn main() -> Result<(), &'static str>{
 
@@ -0,0 +1,142 @@
+// card.rs
+use std::fs::File;
+use std::io::{self, BufReader, Stdout, Write};
+use std::path::Path;
+
+use super::device::{block_to_text, read_text_block, Device, LineInput, StdinInput};
+use super::word::Word;
+
+// 80 columns, five characters to a word
+pub const CARD_BLOCK: usize = 16;
+
+// The card reader, unit 16: each IN reads the next line of the input, such
+// as a host file or stdin, as one card.
+pub struct CardReader<R: LineInput> {
+    input: R,
+}
+
+impl<R: LineInput> CardReader<R> {
+    pub fn new(input: R) -> Self {
+        CardReader { input }
+    }
+}
+
+impl CardReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let file = File::open(path).map_err(|_| "Cannot open card deck")?;
+        Ok(CardReader::new(BufReader::new(file)))
+    }
+}
+
+impl CardReader<StdinInput> {
+    pub fn stdin() -> Self {
+        CardReader::new(StdinInput)
+    }
+}
+
+impl<R: LineInput> Device for CardReader<R> {
+    fn block_size(&self) -> usize {
+        CARD_BLOCK
+    }
+
+    fn read_block(&mut self, _position: i64) -> Result<Vec<Word>, &'static str> {
+        read_text_block(&mut self.input, CARD_BLOCK, "Cannot read card deck")?.ok_or("End of card deck")
+    }
+}
+
+// The card punch, unit 17: each OUT writes one card as a line of the
+// output, such as a host file or stdout.
+pub struct CardPunch<W: Write> {
+    output: W,
+}
+
+impl<W: Write> CardPunch<W> {
+    pub fn new(output: W) -> Self {
+        CardPunch { output }
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+}
+
+impl CardPunch<File> {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let file = File::create(path).map_err(|_| "Cannot create card punch output")?;
+        Ok(CardPunch::new(file))
+    }
+}
+
+impl CardPunch<Stdout> {
+    pub fn stdout() -> Self {
+        CardPunch::new(io::stdout())
+    }
+}
+
+impl<W: Write> Device for CardPunch<W> {
+    fn block_size(&self) -> usize {
+        CARD_BLOCK
+    }
+
+    fn write_block(&mut self, _position: i64, block: &[Word]) -> Result<(), &'static str> {
+        let line = block_to_text(block)?;
+        writeln!(self.output, "{}", line).map_err(|_| "Cannot write card punch output")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mix::device::{SharedOutput, CARD_PUNCH, CARD_READER};
+    use crate::mix::instructions::{assemble, Instruction, HLT, IN, OUT, SPECIAL};
+    use crate::mix::machine::{Mix, MixConfig};
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn test_reader_converts_lines() {
+        let mut reader = CardReader::new(Cursor::new("PRIME NUMBERS\r\n\nx".to_string()));
+        let card = reader.read_block(0).unwrap();
+        assert_eq!(card.len(), CARD_BLOCK);
+        assert_eq!(card[0].bytes, [17, 19, 9, 14, 5]);
+        assert_eq!(block_to_text(&card).unwrap(), "PRIME NUMBERS");
+        assert_eq!(reader.read_block(0).unwrap(), vec![Word::ZERO; CARD_BLOCK]);
+        assert_eq!(block_to_text(&reader.read_block(0).unwrap()).unwrap(), "X");
+        assert_eq!(reader.read_block(0), Err("End of card deck"));
+    }
+
+    #[test]
+    fn test_reader_rejects_long_cards() {
+        let mut reader = CardReader::new(Cursor::new("9".repeat(81)));
+        assert_eq!(reader.read_block(0), Err("Line does not fit in a block"));
+    }
+
+    #[test]
+    fn test_reader_takes_one_line_per_card() {
+        // the rest of the input stays with its owner
+        let mut input = Cursor::new("FIRST CARD\nNOT YET READ\n".to_string());
+        let card = CardReader::new(&mut input).read_block(0).unwrap();
+        assert_eq!(block_to_text(&card).unwrap(), "FIRST CARD");
+        let mut rest = String::new();
+        input.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "NOT YET READ\n");
+    }
+
+    #[test]
+    fn test_copy_a_deck() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let deck = "FIRST CARD\nSECOND CARD (2)\n";
+        let output = SharedOutput::default();
+        mix.attach_device(CARD_READER, Box::new(CardReader::new(Cursor::new(deck.to_string())))).unwrap();
+        mix.attach_device(CARD_PUNCH, Box::new(CardPunch::new(output.clone()))).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, CARD_READER, IN),
+            Instruction::new(100, 0, CARD_PUNCH, OUT),
+            Instruction::new(100, 0, CARD_READER, IN),
+            Instruction::new(100, 0, CARD_PUNCH, OUT),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(output.text(), deck);
+    }
+}
@@ -0,0 +1,48 @@
+// charset.rs
+
+// The MIX character set, indexed by character code. Δ, Σ and Π stand in
+// for the book's special letters at codes 10, 20 and 21.
+const CHARACTERS: [char; 56] = [
+    ' ', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I',
+    'Δ', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R',
+    'Σ', 'Π', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
+    '.', ',', '(', ')', '+', '-', '*', '/', '=', '$',
+    '<', '>', '@', ';', ':', '\'',
+];
+
+// character code of the digit 0; the other digits follow it
+pub const ZERO: u8 = 30;
+
+pub fn to_char(code: u8) -> Option<char> {
+    CHARACTERS.get(code as usize).copied()
+}
+
+pub fn from_char(c: char) -> Option<u8> {
+    CHARACTERS.iter().position(|&m| m == c).map(|code| code as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_character_codes() {
+        assert_eq!(from_char(' '), Some(0));
+        assert_eq!(from_char('A'), Some(1));
+        assert_eq!(from_char('J'), Some(11));
+        assert_eq!(from_char('S'), Some(22));
+        assert_eq!(from_char('0'), Some(ZERO));
+        assert_eq!(from_char('\''), Some(55));
+        assert_eq!(from_char('a'), None);
+        assert_eq!(to_char(20), Some('Σ'));
+        assert_eq!(to_char(56), None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for code in 0..56 {
+            assert_eq!(from_char(to_char(code).unwrap()), Some(code));
+        }
+    }
+}
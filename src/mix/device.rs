@@ -0,0 +1,192 @@
+// device.rs
+use std::io::{self, BufRead};
+use std::ops::RangeInclusive;
+
+use super::charset;
+use super::word::{Sign, Word};
+
+// number of I/O units, numbered 0 to 20 as in TAOCP section 1.3.1
+pub const UNITS: usize = 21;
+// the units of each kind of device
+pub const TAPES: RangeInclusive<u8> = 0..=7;
+pub const DISKS: RangeInclusive<u8> = 8..=15;
+pub const CARD_READER: u8 = 16;
+pub const CARD_PUNCH: u8 = 17;
+pub const PRINTER: u8 = 18;
+pub const TYPEWRITER: u8 = 19;
+pub const PAPER_TAPE: u8 = 20;
+
+// An input-output unit that can be attached to a Mix. IN and OUT transfer
+// one block of block_size() words; position is the contents of rX, which
+// only the disks and drums use. A device stays busy for operation_time()
+// units u after each operation, during which JBUS jumps and further
+// operations on the unit wait.
+pub trait Device {
+    fn block_size(&self) -> usize;
+
+    fn read_block(&mut self, _position: i64) -> Result<Vec<Word>, &'static str> {
+        Err("Device cannot be read")
+    }
+
+    fn write_block(&mut self, _position: i64, _block: &[Word]) -> Result<(), &'static str> {
+        Err("Device cannot be written")
+    }
+
+    // IOC with the effective address M
+    fn control(&mut self, _m: i64, _position: i64) -> Result<(), &'static str> {
+        Err("Device has no control operation")
+    }
+
+    fn operation_time(&self) -> u64 {
+        0
+    }
+}
+
+// Words as stored in the host files behind tapes and disks: a sign byte,
+// 0 for + and 1 for -, followed by the five bytes.
+pub const WORD_BYTES: usize = 6;
+
+pub fn word_to_bytes(word: &Word) -> [u8; WORD_BYTES] {
+    let mut bytes = [0; WORD_BYTES];
+    bytes[0] = (word.sign == Sign::Minus) as u8;
+    bytes[1..].copy_from_slice(&word.bytes);
+    bytes
+}
+
+pub fn word_from_bytes(bytes: &[u8]) -> Result<Word, &'static str> {
+    let sign = match bytes.first() {
+        Some(0) => Sign::Plus,
+        Some(1) => Sign::Minus,
+        _ => return Err("Corrupt word in device file"),
+    };
+    let bytes = bytes[1..].try_into().map_err(|_| "Corrupt word in device file")?;
+    Ok(Word::new(sign, bytes))
+}
+
+pub fn block_to_bytes(block: &[Word]) -> Vec<u8> {
+    block.iter().flat_map(word_to_bytes).collect()
+}
+
+pub fn block_from_bytes(bytes: &[u8]) -> Result<Vec<Word>, &'static str> {
+    bytes.chunks(WORD_BYTES).map(word_from_bytes).collect()
+}
+
+// Character devices hold five MIX character codes per word. A line of text
+// becomes a block padded with spaces; lowercase letters are taken as
+// uppercase, since MIX has no others.
+pub fn text_to_block(line: &str, words: usize) -> Result<Vec<Word>, &'static str> {
+    let mut codes = line
+        .chars()
+        .map(|c| charset::from_char(c.to_ascii_uppercase()).ok_or("Character not in the MIX character set"))
+        .collect::<Result<Vec<u8>, _>>()?;
+    if codes.len() > 5 * words {
+        return Err("Line does not fit in a block");
+    }
+    codes.resize(5 * words, 0);
+    Ok(codes.chunks(5).map(|bytes| Word::new(Sign::Plus, bytes.try_into().unwrap())).collect())
+}
+
+// the text of a block of character codes, without trailing spaces
+pub fn block_to_text(block: &[Word]) -> Result<String, &'static str> {
+    let text = block
+        .iter()
+        .flat_map(|word| word.bytes)
+        .map(|code| charset::to_char(code).ok_or("Invalid character code"))
+        .collect::<Result<String, _>>()?;
+    Ok(text.trim_end_matches(' ').to_string())
+}
+
+// A source of lines for the character input devices: any BufRead, or
+// StdinInput for standard input.
+pub trait LineInput {
+    fn next_line(&mut self, line: &mut String) -> io::Result<usize>;
+}
+
+impl<R: BufRead> LineInput for R {
+    fn next_line(&mut self, line: &mut String) -> io::Result<usize> {
+        self.read_line(line)
+    }
+}
+
+// Standard input, locked only while each line is read so that several
+// devices and the embedding program can all read from it.
+pub struct StdinInput;
+
+impl LineInput for StdinInput {
+    fn next_line(&mut self, line: &mut String) -> io::Result<usize> {
+        io::stdin().lock().read_line(line)
+    }
+}
+
+// the next line of the input as a block of character codes; None at the
+// end of the input, and the device's error if the input cannot be read
+pub fn read_text_block(
+    input: &mut impl LineInput,
+    words: usize,
+    error: &'static str,
+) -> Result<Option<Vec<Word>>, &'static str> {
+    let mut line = String::new();
+    match input.next_line(&mut line) {
+        Ok(0) => Ok(None),
+        Ok(_) => text_to_block(line.trim_end_matches(['\n', '\r']), words).map(Some),
+        Err(_) => Err(error),
+    }
+}
+
+// a fresh path in the temporary directory for a test's host file
+#[cfg(test)]
+pub fn temp_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("mix-vm-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+// an output that a test can still read after the device writing to it has
+// been attached to a Mix
+#[cfg(test)]
+#[derive(Clone, Default)]
+pub struct SharedOutput(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+#[cfg(test)]
+impl SharedOutput {
+    pub fn text(&self) -> String {
+        String::from_utf8(self.0.borrow().clone()).unwrap()
+    }
+}
+
+#[cfg(test)]
+impl std::io::Write for SharedOutput {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0.borrow_mut().write(bytes)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_bytes_round_trip() {
+        let block = [Word::new(Sign::Minus, [1, 2, 3, 4, 99]), Word::ZERO];
+        let bytes = block_to_bytes(&block);
+        assert_eq!(bytes, [1, 1, 2, 3, 4, 99, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(block_from_bytes(&bytes).unwrap(), block);
+        assert!(word_from_bytes(&[2, 0, 0, 0, 0, 0]).is_err());
+        assert!(word_from_bytes(&[0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_text_blocks() {
+        let block = text_to_block("Hello, 42", 2).unwrap();
+        assert_eq!(block[0].bytes, [8, 5, 13, 13, 16]);
+        assert_eq!(block[1].bytes, [41, 0, 34, 32, 0]);
+        assert_eq!(block_to_text(&block).unwrap(), "HELLO, 42");
+        assert_eq!(text_to_block("ABCDEFGHIJK", 2), Err("Line does not fit in a block"));
+        assert_eq!(text_to_block("#", 2), Err("Character not in the MIX character set"));
+        assert_eq!(block_to_text(&[Word::new(Sign::Plus, [1, 60, 0, 0, 0])]), Err("Invalid character code"));
+    }
+}
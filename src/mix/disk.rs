@@ -0,0 +1,168 @@
+// disk.rs
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::device::{block_from_bytes, block_to_bytes, Device, WORD_BYTES};
+use super::word::Word;
+
+pub const DISK_BLOCK: usize = 100;
+
+const BLOCK_BYTES: u64 = (DISK_BLOCK * WORD_BYTES) as u64;
+
+// A disk or drum unit kept in a host image file of fixed size. IN and OUT
+// transfer the block whose address is in rX, and IOC 0 moves the head to
+// that block ahead of time. With a seek time, each operation keeps the unit
+// busy for that many units u per block the head travels.
+pub struct Disk {
+    file: File,
+    blocks: u64,
+    // the block under the head
+    head: u64,
+    seek_time: u64,
+    last_operation_time: u64,
+}
+
+impl Disk {
+    // opens the image in the file, creating or extending it with zero words
+    // to hold the number of blocks
+    pub fn open(path: impl AsRef<Path>, blocks: u64) -> Result<Self, &'static str> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|_| "Cannot open disk image")?;
+        let size = file.metadata().map_err(|_| "Cannot open disk image")?.len();
+        if size < blocks * BLOCK_BYTES {
+            file.set_len(blocks * BLOCK_BYTES).map_err(|_| "Cannot open disk image")?;
+        }
+        Ok(Disk { file, blocks, head: 0, seek_time: 0, last_operation_time: 0 })
+    }
+
+    pub fn with_seek_time(mut self, time_per_block: u64) -> Self {
+        self.seek_time = time_per_block;
+        self
+    }
+
+    pub fn blocks(&self) -> u64 {
+        self.blocks
+    }
+
+    pub fn head(&self) -> u64 {
+        self.head
+    }
+
+    // moves the head to the block in rX, timing the travel
+    fn seek(&mut self, position: i64) -> Result<(), &'static str> {
+        if position < 0 || position as u64 >= self.blocks {
+            return Err("Block address out of range");
+        }
+        let block = position as u64;
+        self.last_operation_time = self.head.abs_diff(block) * self.seek_time;
+        self.head = block;
+        self.file.seek(SeekFrom::Start(block * BLOCK_BYTES)).map_err(|_| "Cannot seek disk image")?;
+        Ok(())
+    }
+}
+
+impl Device for Disk {
+    fn block_size(&self) -> usize {
+        DISK_BLOCK
+    }
+
+    fn read_block(&mut self, position: i64) -> Result<Vec<Word>, &'static str> {
+        self.seek(position)?;
+        let mut bytes = vec![0; BLOCK_BYTES as usize];
+        self.file.read_exact(&mut bytes).map_err(|_| "Cannot read disk image")?;
+        block_from_bytes(&bytes)
+    }
+
+    fn write_block(&mut self, position: i64, block: &[Word]) -> Result<(), &'static str> {
+        self.seek(position)?;
+        self.file.write_all(&block_to_bytes(block)).map_err(|_| "Cannot write disk image")
+    }
+
+    // IOC 0 seeks to the block in rX; other values of M are undefined
+    fn control(&mut self, m: i64, position: i64) -> Result<(), &'static str> {
+        if m != 0 {
+            return Err("Invalid disk control");
+        }
+        self.seek(position)
+    }
+
+    fn operation_time(&self) -> u64 {
+        self.last_operation_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mix::device::{temp_path, DISKS};
+    use crate::mix::instructions::{assemble, Instruction, ENT, ENX, HLT, IN, IOC, OUT, SPECIAL};
+    use crate::mix::machine::{Mix, MixConfig};
+    use crate::mix::word::Sign;
+
+    fn block(value: u8) -> Vec<Word> {
+        vec![Word::new(Sign::Plus, [value, 0, 0, 0, value]); DISK_BLOCK]
+    }
+
+    #[test]
+    fn test_random_access_blocks() {
+        let path = temp_path("disk-random");
+        let mut disk = Disk::open(&path, 10).unwrap();
+        assert_eq!(disk.read_block(4).unwrap(), vec![Word::ZERO; DISK_BLOCK]);
+        disk.write_block(7, &block(7)).unwrap();
+        disk.write_block(2, &block(2)).unwrap();
+        assert_eq!(disk.read_block(7).unwrap(), block(7));
+        assert_eq!(disk.read_block(2).unwrap(), block(2));
+        assert_eq!(disk.read_block(10), Err("Block address out of range"));
+        assert_eq!(disk.read_block(-1), Err("Block address out of range"));
+        drop(disk);
+
+        let mut disk = Disk::open(&path, 10).unwrap();
+        assert_eq!(disk.read_block(7).unwrap(), block(7));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_seek_time() {
+        let path = temp_path("disk-seek");
+        let mut disk = Disk::open(&path, 100).unwrap().with_seek_time(3);
+        disk.control(0, 40).unwrap();
+        assert_eq!((disk.head(), disk.operation_time()), (40, 120));
+        disk.read_block(40).unwrap();
+        assert_eq!(disk.operation_time(), 0);
+        disk.read_block(30).unwrap();
+        assert_eq!(disk.operation_time(), 30);
+        assert_eq!(disk.control(1, 0), Err("Invalid disk control"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_rx_selects_the_block() {
+        let path = temp_path("disk-machine");
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let unit = *DISKS.start();
+        mix.attach_device(unit, Box::new(Disk::open(&path, 20).unwrap().with_seek_time(10))).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(15, 0, ENT, ENX),
+            Instruction::new(0, 0, unit, IOC),
+            Instruction::new(1000, 0, unit, OUT),
+            Instruction::new(2000, 0, unit, IN),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.set_memory(1099, Word::from_value(-5, b)).unwrap();
+        // the OUT waits for the 150u seek to block 15 started by the IOC
+        assert_eq!(mix.run().unwrap(), 1 + 150 + 1 + 1 + 10);
+        assert_eq!(mix.read_memory(2099).unwrap().value(b), -5);
+        drop(mix);
+
+        let mut disk = Disk::open(&path, 20).unwrap();
+        assert_eq!(disk.read_block(15).unwrap()[99].value(b), -5);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
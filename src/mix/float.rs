@@ -0,0 +1,307 @@
+// float.rs
+use super::machine::Comparison;
+use super::word::{ByteSize, Sign, Word};
+
+// Floating-point words as in TAOCP section 4.2.1: the sign, an exponent byte
+// e in excess q = b/2 notation, and a four-byte fraction f, for the value
+// ±f × b^(e - q) with 0 <= f < 1. A normalized number has a nonzero
+// leading fraction byte or is zero altogether.
+
+// the excess q of the exponent byte
+pub fn excess(byte_size: ByteSize) -> i64 {
+    byte_size.base() / 2
+}
+
+// sign, exponent byte and fraction bytes of a word as an integer F,
+// so that the value is ±F × b^(e - q - 4)
+fn unpack(word: &Word, byte_size: ByteSize) -> (Sign, i64, u128) {
+    let base = byte_size.base() as u128;
+    let fraction = word.bytes[1..].iter().fold(0u128, |acc, &b| acc * base + b as u128);
+    (word.sign, word.bytes[0] as i64, fraction)
+}
+
+// the power of b by which F is multiplied
+fn scale(exponent: i64, byte_size: ByteSize) -> i64 {
+    exponent - excess(byte_size) - 4
+}
+
+// rounds m / divisor to the nearest integer, ties to even
+fn round(m: u128, divisor: u128) -> u128 {
+    let (quotient, remainder) = (m / divisor, m % divisor);
+    let half = divisor / 2;
+    if remainder > half || (remainder == half && quotient % 2 == 1) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+// Algorithm 4.2.1N: packs ±m × b^power into a normalized floating-point word
+// rounded to four fraction bytes. The flag reports exponent overflow or
+// underflow, in which case the exponent byte is kept modulo b.
+fn normalize(sign: Sign, mut m: u128, mut power: i64, byte_size: ByteSize) -> (Word, bool) {
+    if m == 0 {
+        return (Word::ZERO, false);
+    }
+    let base = byte_size.base() as u128;
+    let low = base.pow(3);
+    let high = base.pow(4);
+    while m < low {
+        m *= base;
+        power -= 1;
+    }
+    let mut divisor = 1;
+    while m / divisor >= high {
+        divisor *= base;
+        power += 1;
+    }
+    m = round(m, divisor);
+    if m == high {
+        m = low;
+        power += 1;
+    }
+
+    let exponent = power + excess(byte_size) + 4;
+    let out_of_range = exponent < 0 || exponent >= base as i64;
+    let mut bytes = [0; 5];
+    bytes[0] = exponent.rem_euclid(base as i64) as u8;
+    for byte in bytes[1..].iter_mut().rev() {
+        *byte = (m % base) as u8;
+        m /= base;
+    }
+    (Word::new(sign, bytes), out_of_range)
+}
+
+// FADD, and FSUB with a negated v
+pub fn add(u: &Word, v: &Word, byte_size: ByteSize) -> (Word, bool) {
+    let (u, v) = if v.bytes[0] > u.bytes[0] { (v, u) } else { (u, v) };
+    let (su, eu, fu) = unpack(u, byte_size);
+    let (sv, ev, fv) = unpack(v, byte_size);
+    if fu == 0 {
+        return normalize(sv, fv, scale(ev, byte_size), byte_size);
+    }
+    if fv == 0 {
+        return normalize(su, fu, scale(eu, byte_size), byte_size);
+    }
+    // seven guard bytes; a smaller operand shifted out entirely only
+    // matters for rounding, so it is kept as a single unit
+    let base = byte_size.base() as u128;
+    let shift = eu - ev;
+    let mu = (fu * base.pow(7)) as i128;
+    let mv = if shift <= 7 { (fv * base.pow(7 - shift as u32)) as i128 } else { 1 };
+    let signed = |sign: Sign, m: i128| if sign == Sign::Minus { -m } else { m };
+    let sum = signed(su, mu) + signed(sv, mv);
+    let sign = if sum < 0 { Sign::Minus } else { Sign::Plus };
+    normalize(sign, sum.unsigned_abs(), scale(eu, byte_size) - 7, byte_size)
+}
+
+// FMUL
+pub fn mul(u: &Word, v: &Word, byte_size: ByteSize) -> (Word, bool) {
+    let (su, eu, fu) = unpack(u, byte_size);
+    let (sv, ev, fv) = unpack(v, byte_size);
+    let sign = if su == sv { Sign::Plus } else { Sign::Minus };
+    normalize(sign, fu * fv, scale(eu, byte_size) + scale(ev, byte_size), byte_size)
+}
+
+// FDIV; None when dividing by zero
+pub fn div(u: &Word, v: &Word, byte_size: ByteSize) -> Option<(Word, bool)> {
+    let (su, eu, fu) = unpack(u, byte_size);
+    let (sv, ev, fv) = unpack(v, byte_size);
+    if fv == 0 {
+        return None;
+    }
+    // eight extra quotient bytes, plus one that records a nonzero remainder
+    let base = byte_size.base() as u128;
+    let dividend = fu * base.pow(8);
+    let quotient = (dividend / fv) * base + !dividend.is_multiple_of(fv) as u128;
+    let sign = if su == sv { Sign::Plus } else { Sign::Minus };
+    Some(normalize(sign, quotient, eu - ev - 9, byte_size))
+}
+
+// FLOT: the integer in a word as a normalized floating-point number
+pub fn flot(word: &Word, byte_size: ByteSize) -> (Word, bool) {
+    normalize(word.sign, word.magnitude(byte_size) as u128, 0, byte_size)
+}
+
+// FIX: a floating-point number rounded to the nearest integer; None when the
+// integer needs more than five bytes
+pub fn fix(word: &Word, byte_size: ByteSize) -> Option<Word> {
+    let (sign, exponent, fraction) = unpack(word, byte_size);
+    let base = byte_size.base() as u128;
+    let power = scale(exponent, byte_size);
+    let magnitude = if power >= 0 {
+        fraction.checked_mul(base.checked_pow(power as u32)?)?
+    } else if power < -5 {
+        0
+    } else {
+        round(fraction, base.pow(-power as u32))
+    };
+    if magnitude >= byte_size.limit(5) as u128 {
+        return None;
+    }
+    Some(Word::from_value(magnitude as i64, byte_size).with_sign(sign))
+}
+
+// FCMP, following the definitions of section 4.2.2: u and v are equal when
+// |u - v| <= |ε| × b^(max(eu, ev) - q), so that ε = 0 compares exactly
+pub fn compare(u: &Word, v: &Word, epsilon: &Word, byte_size: ByteSize) -> Comparison {
+    let (su, eu, fu) = unpack(u, byte_size);
+    let (sv, ev, fv) = unpack(v, byte_size);
+    let (_, ee, fe) = unpack(epsilon, byte_size);
+    // u - v with seven guard bytes as in FADD, in units of b^(top - q - 11);
+    // a zero operand is aligned with the other so that it shifts out nothing
+    let base = byte_size.base() as u128;
+    let (eu_aligned, ev_aligned) = (if fu == 0 { ev } else { eu }, if fv == 0 { eu } else { ev });
+    let top = eu_aligned.max(ev_aligned);
+    let term = |sign: Sign, fraction: u128, exponent: i64| {
+        let shift = top - exponent;
+        let m = if shift <= 7 { (fraction * base.pow(7 - shift as u32)) as i128 } else { (fraction > 0) as i128 };
+        if sign == Sign::Minus { -m } else { m }
+    };
+    let difference = term(su, fu, eu_aligned) - term(sv, fv, ev_aligned);
+
+    // the tolerance in the same units is fε × b^power; |u - v| < b^12 units
+    let magnitude = difference.unsigned_abs();
+    let power = ee - excess(byte_size) + eu.max(ev) - top + 7;
+    let equal = if fe == 0 || power <= -4 {
+        magnitude == 0
+    } else if power >= 12 {
+        true
+    } else {
+        magnitude * base.pow((-power).max(0) as u32) <= fe * base.pow(power.max(0) as u32)
+    };
+    if equal {
+        Comparison::Equal
+    } else if difference > 0 {
+        Comparison::Greater
+    } else {
+        Comparison::Less
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINARY: ByteSize = ByteSize::Binary;
+    const DECIMAL: ByteSize = ByteSize::Decimal;
+
+    fn float(sign: Sign, bytes: [u8; 5]) -> Word {
+        Word::new(sign, bytes)
+    }
+
+    #[test]
+    fn test_flot_and_fix() {
+        for byte_size in [BINARY, DECIMAL] {
+            for value in [1, -1, 12345, -987654, byte_size.limit(5) / 3] {
+                let (word, overflow) = flot(&Word::from_value(value, byte_size), byte_size);
+                assert!(!overflow);
+                assert_ne!(word.bytes[1], 0, "{} is not normalized", value);
+                let back = fix(&word, byte_size).unwrap().value(byte_size);
+                let tolerance = value.abs() / byte_size.limit(3);
+                assert!((back - value).abs() <= tolerance, "{} became {}", value, back);
+            }
+        }
+        assert_eq!(flot(&Word::from_value(1, DECIMAL), DECIMAL).0, float(Sign::Plus, [51, 1, 0, 0, 0]));
+        assert_eq!(flot(&Word::ZERO, DECIMAL).0, Word::ZERO);
+    }
+
+    #[test]
+    fn test_fix_rounds_to_nearest() {
+        // 2.5 and 3.5 in decimal: 0.025 × 100^1, 0.035 × 100^1
+        let two_and_a_half = float(Sign::Plus, [51, 2, 50, 0, 0]);
+        let three_and_a_half = float(Sign::Minus, [51, 3, 50, 0, 0]);
+        assert_eq!(fix(&two_and_a_half, DECIMAL).unwrap().value(DECIMAL), 2);
+        assert_eq!(fix(&three_and_a_half, DECIMAL).unwrap().value(DECIMAL), -4);
+        assert!(fix(&float(Sign::Plus, [60, 1, 0, 0, 0]), DECIMAL).is_none());
+        assert_eq!(fix(&float(Sign::Plus, [10, 1, 0, 0, 0]), DECIMAL).unwrap().value(DECIMAL), 0);
+    }
+
+    #[test]
+    fn test_add() {
+        // 1 + 0.5 = 1.5 in decimal
+        let one = float(Sign::Plus, [51, 1, 0, 0, 0]);
+        let half = float(Sign::Plus, [50, 50, 0, 0, 0]);
+        assert_eq!(add(&one, &half, DECIMAL), (float(Sign::Plus, [51, 1, 50, 0, 0]), false));
+        // 1 - 0.5
+        assert_eq!(add(&one, &half.negate(), DECIMAL), (half, false));
+        // x - x
+        assert_eq!(add(&one, &one.negate(), DECIMAL), (Word::ZERO, false));
+        // a far smaller operand disappears
+        let tiny = float(Sign::Plus, [20, 1, 0, 0, 0]);
+        assert_eq!(add(&one, &tiny, DECIMAL), (one, false));
+        assert_eq!(add(&tiny.negate(), &one, DECIMAL), (one, false));
+    }
+
+    #[test]
+    fn test_add_rounds_and_renormalizes() {
+        // .99999999 × 100^1 + .00000001 × 100^1 carries into a new byte
+        let almost = float(Sign::Plus, [51, 99, 99, 99, 99]);
+        let unit = float(Sign::Plus, [48, 1, 0, 0, 0]);
+        assert_eq!(add(&almost, &unit, DECIMAL), (float(Sign::Plus, [52, 1, 0, 0, 0]), false));
+        // half a unit in the last place rounds to even
+        let half_unit = float(Sign::Plus, [47, 50, 0, 0, 0]);
+        let odd = float(Sign::Plus, [51, 1, 0, 0, 1]);
+        assert_eq!(add(&odd, &half_unit, DECIMAL).0, float(Sign::Plus, [51, 1, 0, 0, 2]));
+        let even = float(Sign::Plus, [51, 1, 0, 0, 2]);
+        assert_eq!(add(&even, &half_unit, DECIMAL).0, even);
+    }
+
+    #[test]
+    fn test_mul_and_div() {
+        let three = flot(&Word::from_value(3, BINARY), BINARY).0;
+        let seven = flot(&Word::from_value(-7, BINARY), BINARY).0;
+        let (product, overflow) = mul(&three, &seven, BINARY);
+        assert!(!overflow);
+        assert_eq!(fix(&product, BINARY).unwrap().value(BINARY), -21);
+
+        let (quotient, overflow) = div(&product, &three, BINARY).unwrap();
+        assert!(!overflow);
+        assert_eq!(quotient, seven);
+
+        let (third, _) = div(&flot(&Word::from_value(1, DECIMAL), DECIMAL).0, &flot(&Word::from_value(3, DECIMAL), DECIMAL).0, DECIMAL).unwrap();
+        assert_eq!(third, float(Sign::Plus, [50, 33, 33, 33, 33]));
+        assert!(div(&three, &Word::ZERO, BINARY).is_none());
+    }
+
+    #[test]
+    fn test_exponent_overflow_and_underflow() {
+        let big = float(Sign::Plus, [99, 1, 0, 0, 0]);
+        let (result, overflow) = mul(&big, &big, DECIMAL);
+        assert!(overflow);
+        assert_eq!(result.bytes[1..], [1, 0, 0, 0]);
+
+        let small = float(Sign::Plus, [1, 1, 0, 0, 0]);
+        assert!(mul(&small, &small, DECIMAL).1);
+    }
+
+    #[test]
+    fn test_compare() {
+        let one = float(Sign::Plus, [51, 1, 0, 0, 0]);
+        let close = float(Sign::Plus, [51, 1, 0, 0, 1]);
+        assert_eq!(compare(&one, &close, &Word::ZERO, DECIMAL), Comparison::Less);
+        assert_eq!(compare(&close, &one, &Word::ZERO, DECIMAL), Comparison::Greater);
+        assert_eq!(compare(&one, &one.negate(), &Word::ZERO, DECIMAL), Comparison::Greater);
+        // ε = 0.0001 covers the difference of 0.00000001 × 100
+        let epsilon = float(Sign::Plus, [49, 1, 0, 0, 0]);
+        assert_eq!(compare(&one, &close, &epsilon, DECIMAL), Comparison::Equal);
+    }
+
+    #[test]
+    fn test_compare_at_exactly_the_tolerance() {
+        // |u - v| = 0.0c × b^(e - q) is equal, and one unit more is not
+        for byte_size in [BINARY, DECIMAL] {
+            let q = excess(byte_size) as u8;
+            for (a, c) in [(1, 2), (1, 1), (7, 3), (byte_size.base() as u8 - 1, 9)] {
+                let u = float(Sign::Plus, [q, a, c, 0, 0]);
+                let v = float(Sign::Plus, [q, a, 0, 0, 0]);
+                let epsilon = float(Sign::Minus, [q - 1, c, 0, 0, 0]);
+                assert_eq!(compare(&u, &v, &epsilon, byte_size), Comparison::Equal);
+                assert_eq!(compare(&v, &u, &epsilon, byte_size), Comparison::Equal);
+                let beyond = float(Sign::Plus, [q, a, c, 0, 1]);
+                assert_eq!(compare(&beyond, &v, &epsilon, byte_size), Comparison::Greater);
+                assert_eq!(compare(&v, &beyond, &epsilon, byte_size), Comparison::Less);
+            }
+        }
+    }
+}
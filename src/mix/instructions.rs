@@ -1,117 +1,2065 @@
-use super::machine::Mix;
-
-pub const LDA: i32 = 1;
-pub const STA: i32 = 2;
-pub const ADD: i32 = 3;
-pub const SUB: i32 = 4;
-pub const DIV: i32 = 5;
-pub const JMP: i32 = 6;
-pub const JZ: i32 = 7;
-pub const JL: i32 = 8;
-pub const CMP: i32 = 9;
-pub const HLT: i32 = 0;
+use super::charset;
+use super::float;
+use super::machine::{Comparison, Mix};
+use super::word::{double_magnitude, split_double, ByteSize, Field, IndexWord, Sign, Word};
+
+// operation codes (the C byte of an instruction)
+pub const NOP: u8 = 0;
+pub const ADD: u8 = 1;
+pub const SUB: u8 = 2;
+pub const MUL: u8 = 3;
+pub const DIV: u8 = 4;
+pub const SPECIAL: u8 = 5;
+pub const SHIFT: u8 = 6;
+pub const MOVE: u8 = 7;
+pub const LDA: u8 = 8;
+pub const LD1: u8 = 9;
+pub const LD6: u8 = 14;
+pub const LDX: u8 = 15;
+pub const LDAN: u8 = 16;
+pub const LD1N: u8 = 17;
+pub const LD6N: u8 = 22;
+pub const LDXN: u8 = 23;
+pub const STA: u8 = 24;
+pub const ST1: u8 = 25;
+pub const ST6: u8 = 30;
+pub const STX: u8 = 31;
+pub const STJ: u8 = 32;
+pub const STZ: u8 = 33;
+pub const JBUS: u8 = 34;
+pub const IOC: u8 = 35;
+pub const IN: u8 = 36;
+pub const OUT: u8 = 37;
+pub const JRED: u8 = 38;
+pub const JMP: u8 = 39;
+pub const JA: u8 = 40;
+pub const J1: u8 = 41;
+pub const J6: u8 = 46;
+pub const JX: u8 = 47;
+pub const ENA: u8 = 48;
+pub const EN1: u8 = 49;
+pub const EN6: u8 = 54;
+pub const ENX: u8 = 55;
+pub const CMPA: u8 = 56;
+pub const CMP1: u8 = 57;
+pub const CMP6: u8 = 62;
+pub const CMPX: u8 = 63;
+
+// F byte variants for the operation codes that are not memory references
+pub const NUM: u8 = 0;
+pub const CHAR: u8 = 1;
+pub const HLT: u8 = 2;
+pub const AND: u8 = 3;
+pub const OR: u8 = 4;
+pub const XOR: u8 = 5;
+pub const FLOT: u8 = 6;
+pub const FIX: u8 = 7;
+pub const INT: u8 = 9;
+// F byte turning ADD, SUB, MUL, DIV and CMPA into FADD, FSUB, FMUL, FDIV and FCMP
+pub const FLOATING: u8 = 6;
+// shifts
+pub const SLA: u8 = 0;
+pub const SRA: u8 = 1;
+pub const SLAX: u8 = 2;
+pub const SRAX: u8 = 3;
+pub const SLC: u8 = 4;
+pub const SRC: u8 = 5;
+pub const SLB: u8 = 6;
+pub const SRB: u8 = 7;
+// jumps on the toggles and the comparison indicator, shared with JMP
+pub const JSJ: u8 = 1;
+pub const JOV: u8 = 2;
+pub const JNOV: u8 = 3;
+pub const JL: u8 = 4;
+pub const JE: u8 = 5;
+pub const JG: u8 = 6;
+pub const JGE: u8 = 7;
+pub const JNE: u8 = 8;
+pub const JLE: u8 = 9;
+// address transfers shared by ENA, ENi and ENX
+pub const INC: u8 = 0;
+pub const DEC: u8 = 1;
+pub const ENT: u8 = 2;
+pub const ENN: u8 = 3;
+// register tests shared by JA, Ji and JX
+pub const JN: u8 = 0;
+pub const JZ: u8 = 1;
+pub const JP: u8 = 2;
+pub const JNN: u8 = 3;
+pub const JNZ: u8 = 4;
+pub const JNP: u8 = 5;
+// parity tests of the binary extensions, for JA and JX only (JAE, JAO, JXE, JXO)
+pub const JEVEN: u8 = 6;
+pub const JODD: u8 = 7;
+
+// F byte selecting the whole word, (0:5)
+pub const FULL_FIELD: u8 = 5;
+
+// An instruction word decoded into its parts: the sign and magnitude of the
+// address AA (bytes 1-2), the index I (byte 3), the modification F (byte 4)
+// and the operation code C (byte 5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub sign: Sign,
+    pub address: u16,
+    pub index: u8,
+    pub field: u8,
+    pub opcode: u8,
+}
+
+impl Instruction {
+    // panics if |address| does not fit in the u16 AA field; encode() then
+    // checks it against the two bytes of the byte size
+    pub fn new(address: i64, index: u8, field: u8, opcode: u8) -> Self {
+        let magnitude = u16::try_from(address.unsigned_abs()).expect("Address out of range");
+        Instruction {
+            sign: Sign::of(address),
+            address: magnitude,
+            index,
+            field,
+            opcode,
+        }
+    }
+
+    pub fn decode(word: &Word, byte_size: ByteSize) -> Self {
+        let [a1, a2, index, field, opcode] = word.bytes;
+        Instruction {
+            sign: word.sign,
+            address: (a1 as i64 * byte_size.base() + a2 as i64) as u16,
+            index,
+            field,
+            opcode,
+        }
+    }
+
+    pub fn encode(&self, byte_size: ByteSize) -> Result<Word, &'static str> {
+        let base = byte_size.base();
+        let address = self.address as i64;
+        if address >= byte_size.limit(2) {
+            return Err("Address does not fit in two bytes");
+        }
+        if [self.index, self.field, self.opcode].iter().any(|&b| b as i64 >= base) {
+            return Err("Instruction byte out of range");
+        }
+        let bytes = [(address / base) as u8, (address % base) as u8, self.index, self.field, self.opcode];
+        Ok(Word::new(self.sign, bytes))
+    }
+
+    // the signed value of ±AA
+    pub fn address_value(&self) -> i64 {
+        match self.sign {
+            Sign::Plus => self.address as i64,
+            Sign::Minus => -(self.address as i64),
+        }
+    }
+
+    // execution time in units u, as tabulated in TAOCP section 1.3.1
+    pub fn time(&self) -> u64 {
+        match self.opcode {
+            NOP => 1,
+            ADD | SUB if self.field == FLOATING => 4,
+            MUL if self.field == FLOATING => 9,
+            DIV if self.field == FLOATING => 11,
+            SPECIAL if self.field == FLOT || self.field == FIX => 3,
+            SPECIAL if (AND..=XOR).contains(&self.field) => 1,
+            SPECIAL if self.field == INT => 2,
+            CMPA if self.field == FLOATING => 4,
+            ADD | SUB => 2,
+            MUL => 10,
+            DIV => 12,
+            SPECIAL => 10,
+            SHIFT => 2,
+            MOVE => 1 + 2 * self.field as u64,
+            LDA..=STZ => 2,
+            CMPA..=CMPX => 2,
+            _ => 1,
+        }
+    }
+}
+
+// the words of a test program
+#[cfg(test)]
+pub fn assemble(program: &[Instruction], byte_size: ByteSize) -> Vec<Word> {
+    program.iter().map(|i| i.encode(byte_size).unwrap()).collect()
+}
 
 impl Mix {
     // here are the methods for the instructions of the machine
 
 
-    // LDA: loads a value
-    pub fn lda(&mut self, address: usize) -> Result<(), &'static str> {
+    // V: the field (L:R) of the memory word at the given address
+    fn read_field(&self, address: i64, field: u8) -> Result<Word, &'static str> {
+        let field = Field::decode(field)?;
         let value = self.read_memory(address).ok_or("Memory address out of range")?;
+        Ok(value.field(field))
+    }
+
+    // replaces the field (L:R) of the memory word at the given address
+    fn write_field(&mut self, address: i64, field: u8, source: Word) -> Result<(), &'static str> {
+        let field = Field::decode(field)?;
+        let mut value = self.read_memory(address).ok_or("Memory address out of range")?;
+        value.set_field(field, &source);
+        self.set_memory(address, value)
+    }
+
+    // LDA: loads the field (L:R) of a memory word into register A
+    pub fn lda(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
         self.load_a(value);
         Ok(())
     }
 
-    // STA: Stores the value of register A into memory
-    pub fn sta(&mut self, address: usize) -> Result<(), &'static str> {
+    // LDX: loads the field (L:R) of a memory word into register X
+    pub fn ldx(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        self.load_x(value);
+        Ok(())
+    }
+
+    // LDi: loads the field (L:R) of a memory word into index register i,
+    // which fails if the value needs more than two bytes
+    pub fn ldi(&mut self, index: usize, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        self.load_i(index, IndexWord::from_word(&value)?)
+    }
+
+    // LDAN, LDXN, LDiN: same as the loads above with the opposite sign
+    pub fn ldan(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        self.load_a(value.negate());
+        Ok(())
+    }
+
+    pub fn ldxn(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        self.load_x(value.negate());
+        Ok(())
+    }
+
+    pub fn ldin(&mut self, index: usize, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        self.load_i(index, IndexWord::from_word(&value.negate())?)
+    }
+
+    // STA: Stores the low bytes of register A into the field (L:R) of a memory word
+    pub fn sta(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        self.write_field(address, field, self.read_a())
+    }
+
+    // STX: Stores the low bytes of register X into the field (L:R) of a memory word
+    pub fn stx(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        self.write_field(address, field, self.read_x())
+    }
+
+    // STi: Stores index register i, seen as a word with zero bytes 1-3
+    pub fn sti(&mut self, index: usize, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_i(index).ok_or("Index out of range")?;
+        self.write_field(address, field, value.to_word())
+    }
+
+    // STJ: Stores register J, whose sign is always +; MIXAL uses (0:2) by default
+    pub fn stj(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        self.write_field(address, field, self.read_j().to_word())
+    }
+
+    // STZ: Clears the field (L:R) of a memory word
+    pub fn stz(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        self.write_field(address, field, Word::ZERO)
+    }
+
+    // the word a register holds after a sum: a sum that needs more than five
+    // bytes turns the overflow toggle on and keeps its low five bytes, and a
+    // zero sum leaves the sign of the register unchanged
+    fn sum_word(&mut self, register: Word, sum: i64) -> Word {
+        let b = self.byte_size();
+        if Word::overflows(sum, b) {
+            self.set_overflow(true);
+        }
+        let mut result = Word::from_value(sum, b);
+        if sum == 0 {
+            result.sign = register.sign;
+        }
+        result
+    }
+
+    // ADD: Adds the field (L:R) of a memory word to the register A
+    pub fn add(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
         let a = self.read_a();
-        self.set_memory(address, a)
+        let b = self.byte_size();
+        let sum = self.sum_word(a, a.value(b) + value.value(b));
+        self.load_a(sum);
+        Ok(())
     }
 
-    // ADD: Adds a value of the memory to the register A
-    pub fn add(&mut self, address: usize) -> Result<(), &'static str> {
-        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+    // SUB: Subtracts the field (L:R) of a memory word from the register A
+    pub fn sub(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
         let a = self.read_a();
-        self.load_a(a + value);
+        let b = self.byte_size();
+        let sum = self.sum_word(a, a.value(b) - value.value(b));
+        self.load_a(sum);
         Ok(())
     }
 
-    pub fn sub(&mut self, address: usize) -> Result<(), &'static str> {
-        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+    // MUL: the ten-byte product of rA and V goes to rAX, both registers
+    // taking the sign of the product
+    pub fn mul(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
         let a = self.read_a();
-        self.load_a(a - value);
+        let b = self.byte_size();
+        let product = a.magnitude(b) as u128 * value.magnitude(b) as u128;
+        let sign = if a.sign == value.sign { Sign::Plus } else { Sign::Minus };
+        let (high, low) = split_double(product, b);
+        self.load_a(Word::new(sign, high));
+        self.load_x(Word::new(sign, low));
         Ok(())
     }
 
-    pub fn div(&mut self, address: usize) -> Result<(), &'static str> {
+    // DIV: divides the ten-byte rAX, signed as rA, by V; the quotient goes to
+    // rA and the remainder, with the old sign of rA, to rX. When the quotient
+    // does not fit in five bytes (V = 0 included) the overflow toggle is
+    // turned on and the registers are left as they were.
+    pub fn div(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        let a = self.read_a();
+        let b = self.byte_size();
+        if a.magnitude(b) >= value.magnitude(b) {
+            self.set_overflow(true);
+            return Ok(());
+        }
+        let dividend = double_magnitude(&a, &self.read_x(), b);
+        let divisor = value.magnitude(b) as u128;
+        let sign = if a.sign == value.sign { Sign::Plus } else { Sign::Minus };
+        self.load_a(Word::from_value((dividend / divisor) as i64, b).with_sign(sign));
+        self.load_x(Word::from_value((dividend % divisor) as i64, b).with_sign(a.sign));
+        Ok(())
+    }
+
+    fn require_floating_point(&self) -> Result<(), &'static str> {
+        if self.config().floating_point {
+            Ok(())
+        } else {
+            Err("Floating-point attachment is not installed")
+        }
+    }
+
+    // loads a floating-point result into rA, turning the overflow toggle on
+    // for exponent overflow or underflow
+    fn load_a_float(&mut self, (result, out_of_range): (Word, bool)) {
+        if out_of_range {
+            self.set_overflow(true);
+        }
+        self.load_a(result);
+    }
+
+    // FADD, FSUB, FMUL, FDIV: floating-point arithmetic on rA and the word at M
+    pub fn fadd(&mut self, address: i64) -> Result<(), &'static str> {
+        self.require_floating_point()?;
+        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+        self.load_a_float(float::add(&self.read_a(), &value, self.byte_size()));
+        Ok(())
+    }
+
+    pub fn fsub(&mut self, address: i64) -> Result<(), &'static str> {
+        self.require_floating_point()?;
+        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+        self.load_a_float(float::add(&self.read_a(), &value.negate(), self.byte_size()));
+        Ok(())
+    }
+
+    pub fn fmul(&mut self, address: i64) -> Result<(), &'static str> {
+        self.require_floating_point()?;
+        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+        self.load_a_float(float::mul(&self.read_a(), &value, self.byte_size()));
+        Ok(())
+    }
+
+    // division by zero turns the overflow toggle on and leaves rA unchanged
+    pub fn fdiv(&mut self, address: i64) -> Result<(), &'static str> {
+        self.require_floating_point()?;
+        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+        match float::div(&self.read_a(), &value, self.byte_size()) {
+            Some(quotient) => self.load_a_float(quotient),
+            None => self.set_overflow(true),
+        }
+        Ok(())
+    }
+
+    // FLOT: converts the integer in rA to floating point
+    pub fn flot(&mut self) -> Result<(), &'static str> {
+        self.require_floating_point()?;
+        self.load_a_float(float::flot(&self.read_a(), self.byte_size()));
+        Ok(())
+    }
+
+    // FIX: rounds the floating-point number in rA to an integer; one that
+    // does not fit turns the overflow toggle on and leaves rA unchanged
+    pub fn fix(&mut self) -> Result<(), &'static str> {
+        self.require_floating_point()?;
+        match float::fix(&self.read_a(), self.byte_size()) {
+            Some(value) => self.load_a(value),
+            None => self.set_overflow(true),
+        }
+        Ok(())
+    }
+
+    // FCMP: compares rA with the word at M as floating-point numbers, taking
+    // the tolerance ε from location 0
+    pub fn fcmp(&mut self, address: i64) -> Result<(), &'static str> {
+        self.require_floating_point()?;
         let value = self.read_memory(address).ok_or("Memory address out of range")?;
-        if value == 0 {
-            return Err("Division by zero");
+        let epsilon = self.read_memory(0).ok_or("Memory address out of range")?;
+        let comparison = float::compare(&self.read_a(), &value, &epsilon, self.byte_size());
+        self.set_comparison(comparison);
+        Ok(())
+    }
+
+    fn require_binary_extensions(&self) -> Result<(), &'static str> {
+        if !self.config().binary_extensions {
+            Err("Binary extensions are not enabled")
+        } else if self.byte_size() != ByteSize::Binary {
+            Err("Binary extensions require a binary byte size")
+        } else {
+            Ok(())
         }
+    }
+
+    // AND, OR, XOR: combine the bits of rA with those of the word at M; the
+    // sign of rA is unchanged
+    fn logical(&mut self, address: i64, operation: impl Fn(u8, u8) -> u8) -> Result<(), &'static str> {
+        self.require_binary_extensions()?;
+        let value = self.read_memory(address).ok_or("Memory address out of range")?;
+        let mut a = self.read_a();
+        for (byte, other) in a.bytes.iter_mut().zip(value.bytes) {
+            *byte = operation(*byte, other);
+        }
+        self.load_a(a);
+        Ok(())
+    }
+
+    pub fn and(&mut self, address: i64) -> Result<(), &'static str> {
+        self.logical(address, |a, v| a & v)
+    }
+
+    pub fn or(&mut self, address: i64) -> Result<(), &'static str> {
+        self.logical(address, |a, v| a | v)
+    }
+
+    pub fn xor(&mut self, address: i64) -> Result<(), &'static str> {
+        self.logical(address, |a, v| a ^ v)
+    }
+
+    // NUM: converts the ten character codes of rAX to a number in rA, each
+    // byte standing for its value mod 10; a number beyond five bytes keeps its
+    // low five bytes and turns the overflow toggle on, and the signs of rA and
+    // rX are unchanged
+    pub fn num(&mut self) {
         let a = self.read_a();
-        self.load_a(a / value);
+        let b = self.byte_size();
+        let number = a.bytes.iter()
+            .chain(self.read_x().bytes.iter())
+            .fold(0i64, |acc, &byte| acc * 10 + (byte % 10) as i64);
+        if Word::overflows(number, b) {
+            self.set_overflow(true);
+        }
+        self.load_a(Word::from_value(number, b).with_sign(a.sign));
+    }
+
+    // CHAR: converts the magnitude of rA to ten decimal digit characters in
+    // rAX, leaving the signs unchanged
+    pub fn char(&mut self) {
+        let mut a = self.read_a();
+        let mut x = self.read_x();
+        let mut number = a.magnitude(self.byte_size());
+        for byte in a.bytes.iter_mut().chain(x.bytes.iter_mut()).rev() {
+            *byte = charset::ZERO + (number % 10) as u8;
+            number /= 10;
+        }
+        self.load_a(a);
+        self.load_x(x);
+    }
+
+    // MOVE: copies count words, one at a time, from the address to the
+    // location in rI1, which is increased by count
+    pub fn move_words(&mut self, address: i64, count: u8) -> Result<(), &'static str> {
+        let b = self.byte_size();
+        for offset in 0..count as i64 {
+            let destination = self.read_i(0).ok_or("Index out of range")?.value(b);
+            if !self.accessible(destination) || !self.accessible(address + offset) {
+                return Err("Memory address out of range");
+            }
+            let value = self.read_memory(address + offset).ok_or("Memory address out of range")?;
+            self.set_memory(destination, value)?;
+            if IndexWord::overflows(destination + 1, b) {
+                return Err("Index register overflow");
+            }
+            self.load_i(0, IndexWord::from_value(destination + 1, b))?;
+        }
+        Ok(())
+    }
+
+    // SLA, SRA: shift the bytes of rA by count positions, bringing in zeros;
+    // signs are never affected by shifts
+    pub fn sla(&mut self, count: usize) {
+        let mut a = self.read_a();
+        shift_left(&mut a.bytes, count);
+        self.load_a(a);
+    }
+
+    pub fn sra(&mut self, count: usize) {
+        let mut a = self.read_a();
+        shift_right(&mut a.bytes, count);
+        self.load_a(a);
+    }
+
+    // SLAX, SRAX: shift rA and rX as a single ten-byte register
+    pub fn slax(&mut self, count: usize) {
+        self.shift_ax(|bytes| shift_left(bytes, count));
+    }
+
+    pub fn srax(&mut self, count: usize) {
+        self.shift_ax(|bytes| shift_right(bytes, count));
+    }
+
+    // SLC, SRC: rotate rA and rX as a single ten-byte register
+    pub fn slc(&mut self, count: usize) {
+        self.shift_ax(|bytes| bytes.rotate_left(count % 10));
+    }
+
+    pub fn src(&mut self, count: usize) {
+        self.shift_ax(|bytes| bytes.rotate_right(count % 10));
+    }
+
+    // SLB, SRB: shift rA and rX as a single 60-bit register by count bits
+    pub fn slb(&mut self, count: usize) {
+        self.shift_bits(|magnitude| magnitude.checked_shl(count as u32).unwrap_or(0));
+    }
+
+    pub fn srb(&mut self, count: usize) {
+        self.shift_bits(|magnitude| magnitude.checked_shr(count as u32).unwrap_or(0));
+    }
+
+    fn shift_bits(&mut self, shift: impl FnOnce(u128) -> u128) {
+        let b = self.byte_size();
+        let (mut a, mut x) = (self.read_a(), self.read_x());
+        let magnitude = shift(double_magnitude(&a, &x, b)) % (b.limit(5) as u128).pow(2);
+        (a.bytes, x.bytes) = split_double(magnitude, b);
+        self.load_a(a);
+        self.load_x(x);
+    }
+
+    fn shift_ax(&mut self, shift: impl FnOnce(&mut [u8; 10])) {
+        let mut a = self.read_a();
+        let mut x = self.read_x();
+        let mut bytes = [0; 10];
+        bytes[..5].copy_from_slice(&a.bytes);
+        bytes[5..].copy_from_slice(&x.bytes);
+        shift(&mut bytes);
+        a.bytes.copy_from_slice(&bytes[..5]);
+        x.bytes.copy_from_slice(&bytes[5..]);
+        self.load_a(a);
+        self.load_x(x);
+    }
+
+    // dispatches the shift selected by the F byte; M is the number of bytes
+    pub fn shift(&mut self, operation: u8, count: i64) -> Result<(), &'static str> {
+        if count < 0 {
+            return Err("Negative shift count");
+        }
+        let count = count as usize;
+        match operation {
+            SLA => self.sla(count),
+            SRA => self.sra(count),
+            SLAX => self.slax(count),
+            SRAX => self.srax(count),
+            SLC => self.slc(count),
+            SRC => self.src(count),
+            SLB => {
+                self.require_binary_extensions()?;
+                self.slb(count);
+            },
+            SRB => {
+                self.require_binary_extensions()?;
+                self.srb(count);
+            },
+            _ => return Err("Invalid shift"),
+        }
         Ok(())
     }
 
+    // the result of INC, DEC, ENT or ENN applied to a register with M, which
+    // keeps the sign of the instruction when it is zero
+    fn address_transfer(&mut self, register: Word, operation: u8, m: Word) -> Result<Word, &'static str> {
+        let b = self.byte_size();
+        match operation {
+            INC => Ok(self.sum_word(register, register.value(b) + m.value(b))),
+            DEC => Ok(self.sum_word(register, register.value(b) - m.value(b))),
+            ENT => Ok(m),
+            ENN => Ok(m.negate()),
+            _ => Err("Invalid address transfer"),
+        }
+    }
+
+    // INCA, DECA, ENTA, ENNA
+    pub fn ena(&mut self, operation: u8, m: Word) -> Result<(), &'static str> {
+        let value = self.address_transfer(self.read_a(), operation, m)?;
+        self.load_a(value);
+        Ok(())
+    }
+
+    // INCX, DECX, ENTX, ENNX
+    pub fn enx(&mut self, operation: u8, m: Word) -> Result<(), &'static str> {
+        let value = self.address_transfer(self.read_x(), operation, m)?;
+        self.load_x(value);
+        Ok(())
+    }
+
+    // INCi, DECi, ENTi, ENNi: a result that needs more than two bytes is
+    // undefined in the book, so it is reported as an error; the operands are
+    // too small for the overflow toggle to be involved
+    pub fn eni(&mut self, index: usize, operation: u8, m: Word) -> Result<(), &'static str> {
+        let register = self.read_i(index).ok_or("Index out of range")?;
+        let value = self.address_transfer(register.to_word(), operation, m)?;
+        let value = IndexWord::from_word(&value).map_err(|_| "Index register overflow")?;
+        self.load_i(index, value)
+    }
+
+    // IN: reads the next block of the unit into the words starting at the
+    // address, after waiting for the unit to become ready
+    pub fn input(&mut self, unit: u8, address: i64) -> Result<(), &'static str> {
+        self.wait_for_unit(unit);
+        let position = self.read_x().value(self.byte_size());
+        let size = self.device_mut(unit)?.block_size() as i64;
+        if !self.accessible(address) || !self.accessible(address + size - 1) {
+            return Err("Memory address out of range");
+        }
+        let block = self.device_mut(unit)?.read_block(position)?;
+        if block.len() as i64 != size {
+            return Err("Device returned a block of the wrong size");
+        }
+        for (offset, word) in block.into_iter().enumerate() {
+            self.set_memory(address + offset as i64, word)?;
+        }
+        self.start_operation(unit)
+    }
+
+    // OUT: writes the block of words starting at the address to the unit
+    pub fn output(&mut self, unit: u8, address: i64) -> Result<(), &'static str> {
+        self.wait_for_unit(unit);
+        let position = self.read_x().value(self.byte_size());
+        let size = self.device_mut(unit)?.block_size() as i64;
+        let block = (address..address + size)
+            .map(|location| self.read_memory(location).filter(|_| self.accessible(location)))
+            .collect::<Option<Vec<Word>>>()
+            .ok_or("Memory address out of range")?;
+        self.device_mut(unit)?.write_block(position, &block)?;
+        self.start_operation(unit)
+    }
+
+    // IOC: a control operation whose meaning depends on the device
+    pub fn ioc(&mut self, unit: u8, m: i64) -> Result<(), &'static str> {
+        self.wait_for_unit(unit);
+        let position = self.read_x().value(self.byte_size());
+        self.device_mut(unit)?.control(m, position)?;
+        self.start_operation(unit)
+    }
+
+    // JRED, JBUS: jump when the unit is ready or busy
+    pub fn jred(&mut self, unit: u8, address: i64) -> Result<(), &'static str> {
+        self.device_mut(unit)?;
+        self.jump_if(!self.is_busy(unit), address)
+    }
+
+    pub fn jbus(&mut self, unit: u8, address: i64) -> Result<(), &'static str> {
+        self.device_mut(unit)?;
+        self.jump_if(self.is_busy(unit), address)
+    }
+
+    // transfers control to the address when the condition holds, leaving in
+    // rJ the location of the instruction that followed the jump
+    fn jump_if(&mut self, condition: bool, address: i64) -> Result<(), &'static str> {
+        if condition {
+            let next = IndexWord::from_value(self.get_location() as i64, self.byte_size());
+            self.set_location(address as i32)?;
+            self.load_j(next);
+        }
+        Ok(())
+    }
 
     // JMP: changes the location of the next instruction to execute
-    pub fn jmp(&mut self, address: usize) {
-        self.set_location(address as i32);
+    pub fn jmp(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(true, address)
+    }
+
+    // JSJ: jumps without touching register J
+    pub fn jsj(&mut self, address: i64) -> Result<(), &'static str> {
+        self.set_location(address as i32)
+    }
+
+    // JOV: jumps if the overflow toggle is on, turning it off
+    pub fn jov(&mut self, address: i64) -> Result<(), &'static str> {
+        let overflow = self.get_overflow();
+        self.set_overflow(false);
+        self.jump_if(overflow, address)
+    }
+
+    // JNOV: jumps if the overflow toggle is off, otherwise turns it off
+    pub fn jnov(&mut self, address: i64) -> Result<(), &'static str> {
+        let overflow = self.get_overflow();
+        self.set_overflow(false);
+        self.jump_if(!overflow, address)
+    }
+
+    // JL, JE, JG, JGE, JNE, JLE: jump on the state of the comparison indicator
+    pub fn jl(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(self.get_comparison() == Comparison::Less, address)
+    }
+
+    pub fn je(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(self.get_comparison() == Comparison::Equal, address)
+    }
+
+    pub fn jg(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(self.get_comparison() == Comparison::Greater, address)
     }
 
-    pub fn jz(&mut self, address: usize) {
-        if self.read_a() == 0 {
-            self.set_location(address as i32);
+    pub fn jge(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(self.get_comparison() != Comparison::Less, address)
+    }
+
+    pub fn jne(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(self.get_comparison() != Comparison::Equal, address)
+    }
+
+    pub fn jle(&mut self, address: i64) -> Result<(), &'static str> {
+        self.jump_if(self.get_comparison() != Comparison::Greater, address)
+    }
+
+    // jumps when a register is negative, zero, positive, nonnegative, nonzero
+    // or nonpositive, as selected by the F byte; -0 counts as zero
+    fn jump_on_register(&mut self, register: Word, test: u8, address: i64) -> Result<(), &'static str> {
+        let zero = register.is_zero();
+        let negative = !zero && register.sign == Sign::Minus;
+        let positive = !zero && register.sign == Sign::Plus;
+        let condition = match test {
+            JN => negative,
+            JZ => zero,
+            JP => positive,
+            JNN => !negative,
+            JNZ => !zero,
+            JNP => !positive,
+            _ => return Err("Invalid register jump"),
+        };
+        self.jump_if(condition, address)
+    }
+
+    // JAE, JAO, JXE, JXO: jump when the register is even or odd
+    fn jump_on_parity(&mut self, register: Word, test: u8, address: i64) -> Result<(), &'static str> {
+        self.require_binary_extensions()?;
+        let even = register.bytes[4].is_multiple_of(2);
+        self.jump_if(if test == JEVEN { even } else { !even }, address)
+    }
+
+    // JAN, JAZ, JAP, JANN, JANZ, JANP, and JAE, JAO
+    pub fn ja(&mut self, test: u8, address: i64) -> Result<(), &'static str> {
+        match test {
+            JEVEN | JODD => self.jump_on_parity(self.read_a(), test, address),
+            _ => self.jump_on_register(self.read_a(), test, address),
         }
     }
 
-    pub fn jl(&mut self, address: usize) {
-      if self.read_a() < 0 {
-          self.set_location(address as i32);
-      }
+    // JXN, JXZ, JXP, JXNN, JXNZ, JXNP, and JXE, JXO
+    pub fn jx(&mut self, test: u8, address: i64) -> Result<(), &'static str> {
+        match test {
+            JEVEN | JODD => self.jump_on_parity(self.read_x(), test, address),
+            _ => self.jump_on_register(self.read_x(), test, address),
+        }
     }
 
-    pub fn cmp(&mut self, address: usize) -> Result<(), &'static str> {
-        let value = self.read_memory(address).ok_or("Memory address out of range")?;
-        let a = self.read_a();
-        self.set_comparison(a - value);
+    // JiN, JiZ, JiP, JiNN, JiNZ, JiNP
+    pub fn ji(&mut self, index: usize, test: u8, address: i64) -> Result<(), &'static str> {
+        let value = self.read_i(index).ok_or("Index out of range")?;
+        self.jump_on_register(value.to_word(), test, address)
+    }
+
+    // sets the comparison indicator from the field (L:R) of a register against
+    // the same field of a memory word; +0 and -0 compare equal
+    fn compare(&mut self, register: Word, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_field(address, field)?;
+        let register = register.field(Field::decode(field)?);
+        let b = self.byte_size();
+        self.set_comparison(Comparison::of(register.value(b), value.value(b)));
         Ok(())
     }
+
+    // CMPA, CMPX, CMPi: compare a register against a memory word
+    pub fn cmpa(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        self.compare(self.read_a(), address, field)
+    }
+
+    pub fn cmpx(&mut self, address: i64, field: u8) -> Result<(), &'static str> {
+        self.compare(self.read_x(), address, field)
+    }
+
+    pub fn cmpi(&mut self, index: usize, address: i64, field: u8) -> Result<(), &'static str> {
+        let value = self.read_i(index).ok_or("Index out of range")?;
+        self.compare(value.to_word(), address, field)
+    }
+}
+
+fn shift_left(bytes: &mut [u8], count: usize) {
+    let count = count.min(bytes.len());
+    bytes.rotate_left(count);
+    let len = bytes.len();
+    bytes[len - count..].fill(0);
+}
+
+fn shift_right(bytes: &mut [u8], count: usize) {
+    let count = count.min(bytes.len());
+    bytes.rotate_right(count);
+    bytes[..count].fill(0);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mix::device::Device;
+    use crate::mix::machine::MixConfig;
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     #[test]
     fn test_lda_works() {
-        let mut mix = Mix::new();
-        mix.set_memory(0, 123).unwrap();
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(0, Word::from_value(123, mix.byte_size())).unwrap();
         mix.display_memory();
 
-        mix.lda(0).unwrap();
+        mix.lda(0, FULL_FIELD).unwrap();
         mix.display_registers();
 
-        assert_eq!(mix.read_a(), 123);
+        assert_eq!(mix.read_a().value(mix.byte_size()), 123);
     }
     #[test]
     fn test_lda_out_of_range_address() {
-        let mut mix = Mix::new();
-        assert!(mix.lda(5000).is_err());
+        let mut mix = Mix::new(MixConfig::default());
+        assert!(mix.lda(5000, FULL_FIELD).is_err());
     }
 
     #[test]
     fn test_sta_works() {
-        let mut mix = Mix::new();
-        mix.load_a(123);
-        mix.sta(0).unwrap();
-        assert_eq!(mix.read_memory(0).unwrap(), 123);
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(Word::from_value(123, mix.byte_size()));
+        mix.sta(0, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_memory(0).unwrap().value(mix.byte_size()), 123);
     }
 
     #[test]
     fn test_sta_out_of_range_address() {
-        let mut mix = Mix::new();
-        assert!(mix.sta(5000).is_err());
+        let mut mix = Mix::new(MixConfig::default());
+        assert!(mix.sta(5000, FULL_FIELD).is_err());
+    }
+
+    fn word(sign: Sign, bytes: [u8; 5]) -> Word {
+        Word::new(sign, bytes)
+    }
+
+    #[test]
+    fn test_lda_partial_fields() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(2000, word(Sign::Minus, [1, 16, 3, 5, 4])).unwrap();
+
+        mix.lda(2000, 13).unwrap(); // (1:5)
+        assert_eq!(mix.read_a(), word(Sign::Plus, [1, 16, 3, 5, 4]));
+        mix.lda(2000, 29).unwrap(); // (3:5)
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 3, 5, 4]));
+        mix.lda(2000, 3).unwrap(); // (0:3)
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0, 0, 1, 16, 3]));
+        mix.lda(2000, 36).unwrap(); // (4:4)
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 0, 0, 5]));
+        mix.lda(2000, 0).unwrap(); // (0:0)
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0; 5]));
+    }
+
+    #[test]
+    fn test_sta_partial_fields() {
+        let original = word(Sign::Minus, [1, 2, 3, 4, 5]);
+        let cases = [
+            (5, word(Sign::Plus, [6, 7, 8, 9, 0])),   // (0:5)
+            (13, word(Sign::Minus, [6, 7, 8, 9, 0])), // (1:5)
+            (45, word(Sign::Minus, [1, 2, 3, 4, 0])), // (5:5)
+            (18, word(Sign::Minus, [1, 0, 3, 4, 5])), // (2:2)
+            (19, word(Sign::Minus, [1, 9, 0, 4, 5])), // (2:3)
+            (1, word(Sign::Plus, [0, 2, 3, 4, 5])),   // (0:1)
+        ];
+        for (field, expected) in cases {
+            let mut mix = Mix::new(MixConfig::default());
+            mix.set_memory(2000, original).unwrap();
+            mix.load_a(word(Sign::Plus, [6, 7, 8, 9, 0]));
+            mix.sta(2000, field).unwrap();
+            assert_eq!(mix.read_memory(2000).unwrap(), expected, "field {}", field);
+        }
+    }
+
+    #[test]
+    fn test_mul() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(word(Sign::Plus, [1, 1, 1, 1, 1]));
+        mix.set_memory(1000, word(Sign::Plus, [1, 1, 1, 1, 1])).unwrap();
+        mix.mul(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 1, 2, 3, 4]));
+        assert_eq!(mix.read_x(), word(Sign::Plus, [5, 4, 3, 2, 1]));
+
+        mix.load_a(word(Sign::Minus, [0, 0, 0, 1, 48]));
+        mix.set_memory(1000, word(Sign::Plus, [2, 0, 0, 0, 0])).unwrap();
+        mix.mul(1000, 9).unwrap(); // (1:1)
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0; 5]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [0, 0, 0, 3, 32]));
+
+        mix.load_a(word(Sign::Minus, [50, 0, 1, 48, 4]));
+        mix.set_memory(1000, word(Sign::Minus, [2, 0, 0, 0, 0])).unwrap();
+        mix.mul(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Plus, [1, 36, 0, 3, 32]));
+        assert_eq!(mix.read_x(), word(Sign::Plus, [8, 0, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_div() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(Word::ZERO);
+        mix.load_x(word(Sign::Plus, [0, 0, 0, 0, 17]));
+        mix.set_memory(1000, word(Sign::Plus, [0, 0, 0, 0, 3])).unwrap();
+        mix.div(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 0, 0, 5]));
+        assert_eq!(mix.read_x(), word(Sign::Plus, [0, 0, 0, 0, 2]));
+
+        mix.load_a(word(Sign::Minus, [0, 0, 0, 0, 0]));
+        mix.load_x(word(Sign::Plus, [19, 19, 0, 3, 1]));
+        mix.set_memory(1000, word(Sign::Minus, [0, 0, 0, 2, 0])).unwrap();
+        mix.div(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 9, 41, 32, 1]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [0, 0, 0, 1, 1]));
+    }
+
+    #[test]
+    fn test_div_quotient_too_large_sets_overflow() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(word(Sign::Plus, [0, 0, 0, 0, 3]));
+        mix.set_memory(1000, word(Sign::Plus, [0, 0, 0, 0, 3])).unwrap();
+        mix.div(1000, FULL_FIELD).unwrap();
+        assert!(mix.get_overflow());
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 0, 0, 3]));
+
+        mix.set_overflow(false);
+        mix.set_memory(1000, Word::ZERO).unwrap();
+        mix.div(1000, FULL_FIELD).unwrap();
+        assert!(mix.get_overflow());
+    }
+
+    #[test]
+    fn test_add_overflow_keeps_low_bytes() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(word(Sign::Plus, [63, 63, 63, 63, 63]));
+        mix.set_memory(1000, word(Sign::Plus, [0, 0, 0, 0, 2])).unwrap();
+        mix.add(1000, FULL_FIELD).unwrap();
+        assert!(mix.get_overflow());
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 0, 0, 1]));
+
+        mix.set_overflow(false);
+        mix.load_a(word(Sign::Minus, [63, 63, 63, 63, 63]));
+        mix.sub(1000, FULL_FIELD).unwrap();
+        assert!(mix.get_overflow());
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0, 0, 0, 0, 1]));
+    }
+
+    #[test]
+    fn test_add_zero_result_keeps_sign_of_a() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(word(Sign::Minus, [0, 0, 0, 0, 5]));
+        mix.set_memory(1000, word(Sign::Plus, [0, 0, 0, 0, 5])).unwrap();
+        mix.add(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0; 5]));
+        assert!(!mix.get_overflow());
+    }
+
+    #[test]
+    fn test_jov_and_jnov_clear_the_toggle() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let program = assemble(&[
+            Instruction::new(100, 0, FULL_FIELD, LDA),
+            Instruction::new(100, 0, FULL_FIELD, ADD),
+            Instruction::new(4, 0, JOV, JMP),
+            Instruction::new(0, 0, HLT, SPECIAL),
+            Instruction::new(101, 0, FULL_FIELD, STA),
+            Instruction::new(7, 0, JNOV, JMP),
+            Instruction::new(0, 0, HLT, SPECIAL),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        mix.set_memory(100, Word::from_value(b.limit(5) - 1, b)).unwrap();
+        mix.run().unwrap();
+        assert!(!mix.get_overflow());
+        assert_eq!(mix.read_memory(101).unwrap().value(b), b.limit(5) - 2);
+        assert_eq!(mix.get_location(), 8);
+    }
+
+    #[test]
+    fn test_mul_div_decimal() {
+        let mut mix = Mix::new(MixConfig::decimal());
+        let b = mix.byte_size();
+        mix.load_a(Word::from_value(-9_999_999_999, b));
+        mix.set_memory(1000, Word::from_value(9_999_999_999, b)).unwrap();
+        mix.mul(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a().value(b), -9_999_999_998);
+        assert_eq!(mix.read_x().value(b), -1);
+        mix.div(1000, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a().value(b), -9_999_999_999);
+        assert_eq!(mix.read_x(), Word::new(Sign::Minus, [0; 5]));
+    }
+
+    #[test]
+    fn test_instruction_encoding_round_trip() {
+        for byte_size in [ByteSize::Binary, ByteSize::Decimal] {
+            let instruction = Instruction::new(-2000, 2, 3, LDA);
+            let word = instruction.encode(byte_size).unwrap();
+            assert_eq!(word.sign, Sign::Minus);
+            assert_eq!(word.bytes[2..], [2, 3, LDA]);
+            assert_eq!(Instruction::decode(&word, byte_size), instruction);
+            assert_eq!(instruction.address_value(), -2000);
+        }
+        assert_eq!(Instruction::new(2000, 0, 5, STA).encode(ByteSize::Binary).unwrap().bytes, [31, 16, 0, 5, 24]);
+    }
+
+    #[test]
+    fn test_instruction_encoding_rejects_large_address() {
+        assert!(Instruction::new(4096, 0, 5, LDA).encode(ByteSize::Binary).is_err());
+        assert!(Instruction::new(4096, 0, 5, LDA).encode(ByteSize::Decimal).is_ok());
+        assert!(Instruction::new(0, 0, 64, LDA).encode(ByteSize::Binary).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Address out of range")]
+    fn test_instruction_rejects_address_beyond_the_aa_field() {
+        // would otherwise wrap around to 100
+        Instruction::new(65636, 0, 5, LDA);
+    }
+
+    #[test]
+    fn test_execute_fetches_one_word_per_instruction() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let program = assemble(&[
+            Instruction::new(10, 0, FULL_FIELD, LDA),
+            Instruction::new(3, 0, JZ, JA),
+            Instruction::new(11, 0, FULL_FIELD, STA),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        mix.set_memory(10, Word::from_value(7, b)).unwrap();
+
+        assert!(!mix.execute().unwrap());
+        assert_eq!(mix.get_location(), 1);
+        assert!(!mix.execute().unwrap());
+        assert_eq!(mix.get_location(), 2);
+        mix.run().unwrap();
+        assert_eq!(mix.read_memory(11).unwrap().value(b), 7);
+        assert_eq!(mix.get_location(), 4);
+    }
+
+    #[test]
+    fn test_indexed_effective_address() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_i(0, IndexWord::from_value(5, b)).unwrap();
+        mix.load_i(5, IndexWord::from_value(-3, b)).unwrap();
+
+        assert_eq!(mix.effective_address(&Instruction::new(1000, 0, 5, LDA)).unwrap(), 1000);
+        assert_eq!(mix.effective_address(&Instruction::new(1000, 1, 5, LDA)).unwrap(), 1005);
+        assert_eq!(mix.effective_address(&Instruction::new(1000, 6, 5, LDA)).unwrap(), 997);
+        assert_eq!(mix.effective_address(&Instruction::new(-5, 1, 5, LDA)).unwrap(), 0);
+        assert!(mix.effective_address(&Instruction::new(1000, 7, 5, LDA)).is_err());
+    }
+
+    #[test]
+    fn test_indexed_load_and_store() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let program = assemble(&[
+            Instruction::new(1000, 1, FULL_FIELD, LDA),
+            Instruction::new(2000, 2, FULL_FIELD, STA),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        mix.set_memory(1003, Word::from_value(42, b)).unwrap();
+        mix.load_i(0, IndexWord::from_value(3, b)).unwrap();
+        mix.load_i(1, IndexWord::from_value(-10, b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_memory(1990).unwrap().value(b), 42);
+    }
+
+    #[test]
+    fn test_effective_address_out_of_range() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[Instruction::new(3999, 1, FULL_FIELD, LDA)], b)).unwrap();
+        mix.load_i(0, IndexWord::from_value(1, b)).unwrap();
+        assert!(mix.execute().is_err());
+
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_program(&assemble(&[Instruction::new(0, 9, FULL_FIELD, LDA)], b)).unwrap();
+        assert!(mix.execute().is_err());
+    }
+
+    #[test]
+    fn test_load_family() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(2000, word(Sign::Minus, [1, 16, 3, 5, 4])).unwrap();
+
+        mix.ldx(2000, 29).unwrap(); // (3:5)
+        assert_eq!(mix.read_x(), word(Sign::Plus, [0, 0, 3, 5, 4]));
+        mix.ldan(2000, 3).unwrap(); // (0:3)
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 1, 16, 3]));
+        mix.ldxn(2000, 13).unwrap(); // (1:5)
+        assert_eq!(mix.read_x(), word(Sign::Minus, [1, 16, 3, 5, 4]));
+        mix.ldan(2000, 0).unwrap(); // (0:0)
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0; 5]));
+    }
+
+    #[test]
+    fn test_index_register_loads() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(2000, word(Sign::Minus, [1, 16, 3, 5, 4])).unwrap();
+
+        mix.ldi(0, 2000, 37).unwrap(); // (4:5)
+        assert_eq!(mix.read_i(0).unwrap(), IndexWord::new(Sign::Plus, [5, 4]));
+        mix.set_memory(2001, word(Sign::Minus, [0, 0, 0, 3, 5])).unwrap();
+        mix.ldin(5, 2001, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_i(5).unwrap(), IndexWord::new(Sign::Plus, [3, 5]));
+        assert!(mix.ldi(2, 2000, FULL_FIELD).is_err());
+        mix.ldin(2, 2000, 2).unwrap(); // (0:2)
+        assert_eq!(mix.read_i(2).unwrap(), IndexWord::new(Sign::Plus, [1, 16]));
+    }
+
+    #[test]
+    fn test_store_family() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(2000, word(Sign::Minus, [1, 2, 3, 4, 5])).unwrap();
+        mix.load_x(word(Sign::Plus, [6, 7, 8, 9, 10]));
+        mix.stx(2000, 12).unwrap(); // (1:4)
+        assert_eq!(mix.read_memory(2000).unwrap(), word(Sign::Minus, [7, 8, 9, 10, 5]));
+
+        mix.load_i(3, IndexWord::new(Sign::Minus, [20, 30])).unwrap();
+        mix.sti(3, 2000, 5).unwrap();
+        assert_eq!(mix.read_memory(2000).unwrap(), word(Sign::Minus, [0, 0, 0, 20, 30]));
+        mix.sti(3, 2001, 9).unwrap(); // (1:1)
+        assert_eq!(mix.read_memory(2001).unwrap(), word(Sign::Plus, [30, 0, 0, 0, 0]));
+
+        mix.set_memory(2002, word(Sign::Minus, [1, 2, 3, 4, 5])).unwrap();
+        mix.stj(2002, 2).unwrap(); // (0:2)
+        assert_eq!(mix.read_memory(2002).unwrap(), word(Sign::Plus, [0, 0, 3, 4, 5]));
+        mix.stz(2002, 29).unwrap(); // (3:5)
+        assert_eq!(mix.read_memory(2002).unwrap(), word(Sign::Plus, [0; 5]));
+    }
+
+    #[test]
+    fn test_load_store_opcodes() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let program = assemble(&[
+            Instruction::new(100, 0, 37, LD1 + 2), // LD3 100(4:5)
+            Instruction::new(100, 0, FULL_FIELD, LDXN),
+            Instruction::new(101, 3, FULL_FIELD, STX),
+            Instruction::new(102, 0, FULL_FIELD, ST1 + 2),
+            Instruction::new(100, 0, FULL_FIELD, STZ),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        mix.set_memory(100, Word::from_value(7, b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_i(2).unwrap().value(b), 7);
+        assert_eq!(mix.read_memory(108).unwrap().value(b), -7);
+        assert_eq!(mix.read_memory(102).unwrap().value(b), 7);
+        assert_eq!(mix.read_memory(100).unwrap(), Word::ZERO);
+    }
+
+    #[test]
+    fn test_instruction_times() {
+        let times = [
+            (Instruction::new(0, 0, 0, NOP), 1),
+            (Instruction::new(0, 0, 5, ADD), 2),
+            (Instruction::new(0, 0, 5, MUL), 10),
+            (Instruction::new(0, 0, 5, DIV), 12),
+            (Instruction::new(0, 0, HLT, SPECIAL), 10),
+            (Instruction::new(0, 0, SLAX, SHIFT), 2),
+            (Instruction::new(0, 0, 0, MOVE), 1),
+            (Instruction::new(0, 0, 10, MOVE), 21),
+            (Instruction::new(0, 0, 5, LD1N + 3), 2),
+            (Instruction::new(0, 0, 2, STJ), 2),
+            (Instruction::new(0, 0, JGE, JMP), 1),
+            (Instruction::new(0, 0, ENT, ENX), 1),
+            (Instruction::new(0, 0, 5, CMPX), 2),
+        ];
+        for (instruction, time) in times {
+            assert_eq!(instruction.time(), time, "{:?}", instruction);
+        }
+    }
+
+    #[test]
+    fn test_run_returns_elapsed_time() {
+        // LDA, 3 x (ADD, DEC1, J1P), HLT = 2 + 3 * (2 + 1 + 1) + 10
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, FULL_FIELD, LDA),
+            Instruction::new(100, 0, FULL_FIELD, ADD),
+            Instruction::new(1, 0, DEC, EN1),
+            Instruction::new(1, 0, JP, J1),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.load_i(0, IndexWord::from_value(3, b)).unwrap();
+        assert_eq!(mix.run().unwrap(), 24);
+        assert_eq!(mix.get_clock(), 24);
+
+        mix.set_location(4).unwrap();
+        assert_eq!(mix.run().unwrap(), 10);
+        assert_eq!(mix.get_clock(), 34);
+    }
+
+    #[test]
+    fn test_profile_counts_each_location() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, FULL_FIELD, LDA),
+            Instruction::new(100, 0, FULL_FIELD, ADD),
+            Instruction::new(1, 0, DEC, EN1),
+            Instruction::new(1, 0, JP, J1),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.load_i(0, IndexWord::from_value(3, b)).unwrap();
+        assert!(mix.profile().is_none());
+        mix.enable_profiling();
+        let time = mix.run().unwrap();
+
+        let profile = mix.profile().unwrap();
+        let counts: Vec<u64> = (0..6).map(|address| profile.count(address)).collect();
+        assert_eq!(counts, [1, 3, 3, 3, 1, 0]);
+        assert_eq!(profile.time(1), 6);
+        assert_eq!(profile.time(4), 10);
+        assert_eq!(profile.total_time(), time);
+    }
+
+    fn floating_point(config: MixConfig) -> Mix {
+        Mix::new(MixConfig { floating_point: true, ..config })
+    }
+
+    #[test]
+    fn test_floating_point_requires_the_attachment() {
+        let mut mix = Mix::new(MixConfig::default());
+        assert!(mix.fadd(0).is_err());
+        assert!(mix.flot().is_err());
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[Instruction::new(0, 0, FLOATING, ADD)], b)).unwrap();
+        assert!(mix.execute().is_err());
+    }
+
+    #[test]
+    fn test_floating_point_program() {
+        // (3 + 4) * 0.5 / 2, fixed to an integer: 1.75 rounds to 2
+        for config in [MixConfig::binary(), MixConfig::decimal()] {
+            let mut mix = floating_point(config);
+            let b = mix.byte_size();
+            mix.load_program(&assemble(&[
+                Instruction::new(100, 0, FULL_FIELD, LDA),
+                Instruction::new(0, 0, FLOT, SPECIAL),
+                Instruction::new(200, 0, FULL_FIELD, STA),
+                Instruction::new(101, 0, FULL_FIELD, LDA),
+                Instruction::new(0, 0, FLOT, SPECIAL),
+                Instruction::new(200, 0, FLOATING, ADD),
+                Instruction::new(102, 0, FLOATING, MUL),
+                Instruction::new(103, 0, FLOATING, DIV),
+                Instruction::new(201, 0, FULL_FIELD, STA),
+                Instruction::new(0, 0, FIX, SPECIAL),
+                Instruction::new(0, 0, HLT, SPECIAL),
+            ], b)).unwrap();
+            mix.set_memory(100, Word::from_value(3, b)).unwrap();
+            mix.set_memory(101, Word::from_value(4, b)).unwrap();
+            let q = b.base() as u8 / 2;
+            mix.set_memory(102, word(Sign::Plus, [q, q, 0, 0, 0])).unwrap(); // 0.5
+            mix.set_memory(103, word(Sign::Plus, [q + 1, 2, 0, 0, 0])).unwrap(); // 2
+            mix.run().unwrap();
+            assert_eq!(mix.read_a().value(b), 2);
+            assert!(!mix.get_overflow());
+            assert_eq!(mix.read_memory(201).unwrap(), word(Sign::Plus, [q + 1, 1, (3 * b.base() / 4) as u8, 0, 0])); // 1.75
+        }
+    }
+
+    #[test]
+    fn test_fsub_fcmp_and_fdiv_by_zero() {
+        let mut mix = floating_point(MixConfig::decimal());
+        mix.set_memory(100, word(Sign::Plus, [51, 1, 0, 0, 0])).unwrap(); // 1
+        mix.load_a(word(Sign::Plus, [51, 1, 50, 0, 0])); // 1.5
+        mix.fsub(100).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Plus, [50, 50, 0, 0, 0]));
+        mix.fcmp(100).unwrap();
+        assert_eq!(mix.get_comparison(), Comparison::Less);
+
+        mix.set_memory(0, word(Sign::Plus, [50, 60, 0, 0, 0])).unwrap(); // ε = 0.6
+        mix.fcmp(100).unwrap();
+        assert_eq!(mix.get_comparison(), Comparison::Equal);
+
+        mix.fdiv(101).unwrap();
+        assert!(mix.get_overflow());
+        assert_eq!(mix.read_a(), word(Sign::Plus, [50, 50, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_floating_point_times() {
+        let times = [
+            (Instruction::new(0, 0, FLOATING, ADD), 4),
+            (Instruction::new(0, 0, FLOATING, SUB), 4),
+            (Instruction::new(0, 0, FLOATING, MUL), 9),
+            (Instruction::new(0, 0, FLOATING, DIV), 11),
+            (Instruction::new(0, 0, FLOT, SPECIAL), 3),
+            (Instruction::new(0, 0, FIX, SPECIAL), 3),
+            (Instruction::new(0, 0, FLOATING, CMPA), 4),
+        ];
+        for (instruction, time) in times {
+            assert_eq!(instruction.time(), time, "{:?}", instruction);
+        }
+    }
+
+    fn binary_extensions() -> Mix {
+        Mix::new(MixConfig { binary_extensions: true, ..MixConfig::binary() })
+    }
+
+    #[test]
+    fn test_binary_extensions_are_opt_in() {
+        let mut mix = Mix::new(MixConfig::binary());
+        assert_eq!(mix.and(0), Err("Binary extensions are not enabled"));
+        let mut mix = Mix::new(MixConfig { binary_extensions: true, ..MixConfig::decimal() });
+        assert_eq!(mix.and(0), Err("Binary extensions require a binary byte size"));
+        assert!(mix.shift(SLB, 1).is_err());
+        assert!(mix.ja(JEVEN, 0).is_err());
+        // the ordinary instructions are unaffected
+        assert!(mix.shift(SLA, 1).is_ok());
+        assert!(mix.ja(JZ, 0).is_ok());
+    }
+
+    #[test]
+    fn test_logical_operations() {
+        let mut mix = binary_extensions();
+        mix.set_memory(100, word(Sign::Plus, [0b111000, 0b101010, 63, 0, 1])).unwrap();
+        let a = word(Sign::Minus, [0b101010, 0b111111, 0, 63, 1]);
+        mix.load_a(a);
+        mix.and(100).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0b101000, 0b101010, 0, 0, 1]));
+        mix.load_a(a);
+        mix.or(100).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0b111010, 0b111111, 63, 63, 1]));
+        mix.load_a(a);
+        mix.xor(100).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0b010010, 0b010101, 63, 63, 0]));
+    }
+
+    #[test]
+    fn test_binary_shifts() {
+        let mut mix = binary_extensions();
+        mix.load_a(word(Sign::Minus, [0, 0, 0, 0, 1]));
+        mix.load_x(word(Sign::Plus, [32, 0, 0, 0, 3]));
+        mix.shift(SLB, 1).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0, 0, 0, 0, 3]));
+        assert_eq!(mix.read_x(), word(Sign::Plus, [0, 0, 0, 0, 6]));
+        mix.shift(SRB, 2).unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [0, 0, 0, 0, 0]));
+        assert_eq!(mix.read_x(), word(Sign::Plus, [48, 0, 0, 0, 1]));
+        mix.shift(SLB, 200).unwrap();
+        assert!(mix.read_a().is_zero() && mix.read_x().is_zero());
+    }
+
+    #[test]
+    fn test_parity_jumps() {
+        let mut mix = binary_extensions();
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(3, 0, JODD, JA),
+            Instruction::new(4, 0, JEVEN, JX),
+            Instruction::new(0, 0, HLT, SPECIAL),
+            Instruction::new(0, 0, HLT, SPECIAL),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.load_a(Word::from_value(-6, b));
+        mix.load_x(Word::from_value(10, b));
+        mix.run().unwrap();
+        assert_eq!(mix.get_location(), 5);
+        assert_eq!(mix.read_j().value(b), 2);
+    }
+
+    // SLB 1, JAE, SRB 1, JAO on the F codes 6 and 7
+    fn extension_codes() -> [Instruction; 4] {
+        [
+            Instruction::new(1, 0, 6, SHIFT),
+            Instruction::new(3, 0, 6, JA),
+            Instruction::new(1, 0, 7, SHIFT),
+            Instruction::new(6, 0, 7, JA),
+        ]
+    }
+
+    #[test]
+    fn test_extension_codes_dispatch_when_enabled() {
+        let mut mix = binary_extensions();
+        let b = mix.byte_size();
+        let [slb, jae, srb, jao] = extension_codes();
+        let halt = Instruction::new(0, 0, HLT, SPECIAL);
+        mix.load_program(&assemble(&[slb, jae, halt, srb, jao, halt, halt], b)).unwrap();
+        mix.load_a(Word::from_value(5, b));
+        mix.run().unwrap();
+        assert_eq!(mix.read_a().value(b), 5);
+        assert_eq!(mix.get_location(), 7);
+    }
+
+    #[test]
+    fn test_extension_codes_are_rejected_when_disabled() {
+        for instruction in extension_codes() {
+            let mut mix = Mix::new(MixConfig::binary());
+            let b = mix.byte_size();
+            mix.load_program(&assemble(&[instruction], b)).unwrap();
+            assert!(mix.execute().is_err(), "{:?}", instruction);
+        }
+    }
+
+    fn with_interrupts() -> Mix {
+        Mix::new(MixConfig { interrupts: true, ..MixConfig::default() })
+    }
+
+    // places the handler at -1000 - 100k and a jump to it in the vector
+    // location, leaving the saved registers and the clock untouched
+    fn load_handler(mix: &mut Mix, vector: i64, k: i64, handler: &[Instruction]) {
+        let b = mix.byte_size();
+        let start = -1000 - 100 * k;
+        mix.set_memory(vector, Instruction::new(start, 0, 0, JMP).encode(b).unwrap()).unwrap();
+        for (offset, instruction) in assemble(handler, b).into_iter().enumerate() {
+            mix.set_memory(start + offset as i64, instruction).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_negative_locations_need_control_state() {
+        let mut mix = Mix::new(MixConfig::default());
+        assert!(mix.read_memory(-10).is_none());
+        assert!(mix.int().is_err());
+
+        let mut mix = with_interrupts();
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[Instruction::new(-10, 0, FULL_FIELD, LDA)], b)).unwrap();
+        assert_eq!(mix.execute(), Err("Effective address out of range"));
+    }
+
+    #[test]
+    fn test_int_saves_and_restores_the_registers() {
+        let mut mix = with_interrupts();
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(5, 0, ENT, ENA),
+            Instruction::new(3, 0, ENT, EN1),
+            Instruction::new(0, 0, INT, SPECIAL),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        // the handler replaces the saved rA and clobbers rI1
+        load_handler(&mut mix, -12, 0, &[
+            Instruction::new(7, 0, ENT, ENA),
+            Instruction::new(-9, 0, FULL_FIELD, STA),
+            Instruction::new(100, 0, ENT, EN1),
+            Instruction::new(0, 0, INT, SPECIAL),
+        ]);
+        mix.set_overflow(true);
+        for _ in 0..3 {
+            mix.execute().unwrap();
+        }
+        assert!(mix.in_control_state());
+        assert_eq!(mix.get_location(), -12);
+        assert_eq!(mix.read_memory(-9).unwrap().value(b), 5);
+        assert_eq!(mix.read_memory(-8).unwrap().value(b), 3);
+        assert_eq!(mix.read_memory(-1).unwrap(), word(Sign::Plus, [0, 3, 4, 0, 0]));
+
+        mix.run().unwrap();
+        assert!(!mix.in_control_state());
+        assert_eq!(mix.get_location(), 4);
+        assert_eq!(mix.read_a().value(b), 7);
+        assert_eq!(mix.read_i(0).unwrap().value(b), 3);
+        assert!(mix.get_overflow());
+        assert_eq!(mix.get_comparison(), Comparison::Equal);
+    }
+
+    #[test]
+    fn test_timer_interrupt() {
+        // spin until the timer handler sets location 100
+        let mut mix = with_interrupts();
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, FULL_FIELD, LDA),
+            Instruction::new(0, 0, JZ, JA),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        load_handler(&mut mix, -11, 0, &[
+            Instruction::new(1, 0, ENT, ENA),
+            Instruction::new(100, 0, FULL_FIELD, STA),
+            Instruction::new(0, 0, INT, SPECIAL),
+        ]);
+        mix.set_memory(-10, Word::from_value(2, b)).unwrap();
+        let time = mix.run().unwrap();
+        assert!((2000..2040).contains(&time));
+        assert_eq!(mix.read_memory(-10).unwrap().value(b), 0);
+        assert_eq!(mix.read_memory(100).unwrap().value(b), 1);
+    }
+
+    #[test]
+    fn test_interrupts_wait_for_normal_state() {
+        let mut mix = with_interrupts();
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(0, 0, INT, SPECIAL),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        load_handler(&mut mix, -12, 0, &[Instruction::new(0, 0, INT, SPECIAL)]);
+        load_handler(&mut mix, -20, 1, &[
+            Instruction::new(1, 0, INC, ENX),
+            Instruction::new(0, 0, INT, SPECIAL),
+        ]);
+        mix.execute().unwrap();
+        mix.interrupt(-20).unwrap();
+        mix.execute().unwrap();
+        mix.execute().unwrap();
+        // the pending interrupt is taken once INT returns to normal state
+        assert_eq!(mix.get_location(), 1);
+        mix.execute().unwrap();
+        assert_eq!(mix.get_location(), -1100);
+        mix.run().unwrap();
+        assert_eq!(mix.read_x().value(b), 0);
+        assert_eq!(mix.get_location(), 2);
+    }
+
+    // a two-word unit that reads back what was last written to it and keeps
+    // a log of its control operations; clones share both
+    #[derive(Clone)]
+    struct Loopback {
+        block: Rc<RefCell<Vec<Word>>>,
+        controls: Rc<RefCell<Vec<(i64, i64)>>>,
+        time: u64,
+    }
+
+    impl Device for Loopback {
+        fn block_size(&self) -> usize {
+            2
+        }
+
+        fn read_block(&mut self, _position: i64) -> Result<Vec<Word>, &'static str> {
+            Ok(self.block.borrow().clone())
+        }
+
+        fn write_block(&mut self, _position: i64, block: &[Word]) -> Result<(), &'static str> {
+            *self.block.borrow_mut() = block.to_vec();
+            Ok(())
+        }
+
+        fn control(&mut self, m: i64, position: i64) -> Result<(), &'static str> {
+            self.controls.borrow_mut().push((m, position));
+            Ok(())
+        }
+
+        fn operation_time(&self) -> u64 {
+            self.time
+        }
+    }
+
+    fn loopback(time: u64) -> Loopback {
+        let block = Rc::new(RefCell::new(vec![Word::ZERO; 2]));
+        Loopback { block, controls: Rc::new(RefCell::new(Vec::new())), time }
+    }
+
+    #[test]
+    fn test_in_and_out() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let device = loopback(0);
+        mix.attach_device(5, Box::new(device.clone())).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, 5, OUT),
+            Instruction::new(200, 0, 5, IN),
+            Instruction::new(-3, 0, 5, IOC),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.set_memory(100, Word::from_value(11, b)).unwrap();
+        mix.set_memory(101, Word::from_value(-22, b)).unwrap();
+        mix.load_x(Word::from_value(9, b));
+        mix.run().unwrap();
+        assert_eq!(device.block.borrow()[1].value(b), -22);
+        assert_eq!(mix.read_memory(200).unwrap().value(b), 11);
+        assert_eq!(mix.read_memory(201).unwrap().value(b), -22);
+        assert_eq!(*device.controls.borrow(), vec![(-3, 9)]);
+    }
+
+    #[test]
+    fn test_io_errors() {
+        let mut mix = Mix::new(MixConfig::default());
+        assert_eq!(mix.input(3, 0), Err("No device attached to unit"));
+        assert_eq!(mix.attach_device(21, Box::new(loopback(0))), Err("Invalid unit"));
+        mix.attach_device(3, Box::new(loopback(0))).unwrap();
+        assert_eq!(mix.input(3, 3999), Err("Memory address out of range"));
+        assert_eq!(mix.output(3, 3999), Err("Memory address out of range"));
+        assert!(mix.input(3, 3998).is_ok());
+        assert!(mix.detach_device(3).is_some());
+        assert!(mix.jred(3, 0).is_err());
+    }
+
+    #[test]
+    fn test_busy_units_delay_the_program() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.attach_device(7, Box::new(loopback(100))).unwrap();
+        // the second OUT waits for the first; JBUS then spins until ready
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, 7, OUT),
+            Instruction::new(100, 0, 7, OUT),
+            Instruction::new(2, 0, 7, JBUS),
+            Instruction::new(5, 0, 7, JRED),
+            Instruction::new(0, 0, HLT, SPECIAL),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        assert_eq!(mix.run().unwrap(), 212);
+        assert_eq!(mix.get_location(), 6);
+        assert!(!mix.is_busy(7));
+    }
+
+    #[test]
+    fn test_completion_interrupts() {
+        let mut mix = with_interrupts();
+        let b = mix.byte_size();
+        mix.attach_device(5, Box::new(loopback(10))).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, 5, OUT),
+            Instruction::new(300, 0, FULL_FIELD, LDA),
+            Instruction::new(1, 0, JZ, JA),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        load_handler(&mut mix, -25, 0, &[
+            Instruction::new(1, 0, ENT, ENA),
+            Instruction::new(300, 0, FULL_FIELD, STA),
+            Instruction::new(0, 0, INT, SPECIAL),
+        ]);
+        mix.run().unwrap();
+        assert_eq!(mix.read_memory(300).unwrap().value(b), 1);
+    }
+
+    #[test]
+    fn test_invalid_field_is_rejected() {
+        let mut mix = Mix::new(MixConfig::default());
+        assert!(mix.lda(0, 8 * 4 + 2).is_err());
+        assert!(mix.sta(0, 6).is_err());
+    }
+
+    #[test]
+    fn test_cmp_ignores_bytes_outside_the_field() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(0, word(Sign::Minus, [9, 9, 1, 2, 3])).unwrap();
+        mix.load_a(word(Sign::Plus, [0, 0, 1, 2, 3]));
+        mix.cmpa(0, 29).unwrap(); // (3:5)
+        assert_eq!(mix.get_comparison(), Comparison::Equal);
+        mix.cmpa(0, 0).unwrap(); // (0:0) is always equal
+        assert_eq!(mix.get_comparison(), Comparison::Equal);
+        mix.cmpa(0, FULL_FIELD).unwrap();
+        assert_eq!(mix.get_comparison(), Comparison::Greater);
+    }
+
+    #[test]
+    fn test_cmpx_and_cmpi() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.set_memory(0, Word::from_value(10, b)).unwrap();
+        mix.load_x(Word::from_value(-10, b));
+        mix.cmpx(0, FULL_FIELD).unwrap();
+        assert_eq!(mix.get_comparison(), Comparison::Less);
+        mix.cmpx(0, 13).unwrap(); // (1:5)
+        assert_eq!(mix.get_comparison(), Comparison::Equal);
+
+        mix.load_i(2, IndexWord::from_value(11, b)).unwrap();
+        mix.cmpi(2, 0, FULL_FIELD).unwrap();
+        assert_eq!(mix.get_comparison(), Comparison::Greater);
+    }
+
+    #[test]
+    fn test_plus_zero_equals_minus_zero() {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.set_memory(0, word(Sign::Minus, [0; 5])).unwrap();
+        mix.cmpa(0, FULL_FIELD).unwrap();
+        assert_eq!(mix.get_comparison(), Comparison::Equal);
+    }
+
+    #[test]
+    fn test_register_jumps() {
+        let values = [
+            (word(Sign::Minus, [0, 0, 0, 0, 1]), [true, false, false, false, true, true]),
+            (word(Sign::Plus, [0; 5]), [false, true, false, true, false, true]),
+            (word(Sign::Minus, [0; 5]), [false, true, false, true, false, true]),
+            (word(Sign::Plus, [0, 0, 0, 0, 1]), [false, false, true, true, true, false]),
+        ];
+        for (value, expected) in values {
+            for (test, taken) in [JN, JZ, JP, JNN, JNZ, JNP].into_iter().zip(expected) {
+                for opcode in [JA, J1, J6, JX] {
+                    let mut mix = Mix::new(MixConfig::default());
+                    let b = mix.byte_size();
+                    mix.load_program(&assemble(&[Instruction::new(100, 0, test, opcode)], b)).unwrap();
+                    mix.load_a(value);
+                    mix.load_x(value);
+                    mix.load_i(0, IndexWord::from_word(&value).unwrap()).unwrap();
+                    mix.load_i(5, IndexWord::from_word(&value).unwrap()).unwrap();
+                    mix.execute().unwrap();
+                    let target = if taken { 100 } else { 1 };
+                    assert_eq!(mix.get_location(), target, "C={} F={} on {}", opcode, test, value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_index_register_controls_a_loop() {
+        // adds the words at 1001..=1003 by counting rI1 down from 3 with
+        // LD1/J1P, in lieu of the address transfer instructions
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let program = assemble(&[
+            Instruction::new(1000, 1, FULL_FIELD, ADD),
+            Instruction::new(2000, 1, FULL_FIELD, LD1),
+            Instruction::new(0, 0, JP, J1),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        for (offset, value) in [(1, 5), (2, 6), (3, 7)] {
+            mix.set_memory(1000 + offset, Word::from_value(value, b)).unwrap();
+            mix.set_memory(2000 + offset, Word::from_value(offset - 1, b)).unwrap();
+        }
+        mix.load_i(0, IndexWord::from_value(3, b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_a().value(b), 18);
+    }
+
+    fn shift_registers() -> Mix {
+        let mut mix = Mix::new(MixConfig::default());
+        mix.load_a(word(Sign::Plus, [1, 2, 3, 4, 5]));
+        mix.load_x(word(Sign::Minus, [6, 7, 8, 9, 10]));
+        mix
+    }
+
+    #[test]
+    fn test_num_and_char() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_a(word(Sign::Minus, [0, 0, 31, 32, 39]));
+        mix.load_x(word(Sign::Plus, [37, 57, 47, 30, 30]));
+        mix.num();
+        assert_eq!(mix.read_a().value(b), -12977700);
+
+        mix.ena(INC, Word::from_value(1, b)).unwrap();
+        mix.char();
+        assert_eq!(mix.read_a(), word(Sign::Minus, [30, 30, 31, 32, 39]));
+        assert_eq!(mix.read_x(), word(Sign::Plus, [37, 37, 36, 39, 39]));
+    }
+
+    #[test]
+    fn test_num_keeps_low_bytes_of_large_numbers() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_a(word(Sign::Plus, [39; 5]));
+        mix.load_x(word(Sign::Plus, [39; 5]));
+        mix.num();
+        assert_eq!(mix.read_a().value(b), 9_999_999_999 % b.limit(5));
+        assert!(mix.get_overflow());
+
+        let mut mix = Mix::new(MixConfig::decimal());
+        let b = mix.byte_size();
+        mix.load_a(word(Sign::Plus, [39; 5]));
+        mix.load_x(word(Sign::Plus, [39; 5]));
+        mix.num();
+        assert_eq!(mix.read_a().value(b), 9_999_999_999);
+        assert!(!mix.get_overflow());
+    }
+
+    #[test]
+    fn test_move() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        for offset in 0..3 {
+            mix.set_memory(1000 + offset, Word::from_value(offset + 1, b)).unwrap();
+        }
+        mix.load_i(0, IndexWord::from_value(999, b)).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(1000, 0, 3, MOVE),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.run().unwrap();
+        for offset in 0..3 {
+            assert_eq!(mix.read_memory(999 + offset).unwrap().value(b), offset + 1);
+        }
+        assert_eq!(mix.read_i(0).unwrap().value(b), 1002);
+    }
+
+    #[test]
+    fn test_overlapping_move_copies_one_word_at_a_time() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.set_memory(1000, Word::from_value(9, b)).unwrap();
+        mix.load_i(0, IndexWord::from_value(1001, b)).unwrap();
+        mix.move_words(1000, 4).unwrap();
+        for offset in 0..5 {
+            assert_eq!(mix.read_memory(1000 + offset).unwrap().value(b), 9);
+        }
+        assert_eq!(mix.read_i(0).unwrap().value(b), 1005);
+    }
+
+    #[test]
+    fn test_sla() {
+        let mut mix = shift_registers();
+        mix.sla(2);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [3, 4, 5, 0, 0]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [6, 7, 8, 9, 10]));
+        mix.sla(7);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0; 5]));
+    }
+
+    #[test]
+    fn test_sra() {
+        let mut mix = shift_registers();
+        mix.sra(2);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 0, 1, 2, 3]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [6, 7, 8, 9, 10]));
+    }
+
+    #[test]
+    fn test_slax() {
+        let mut mix = shift_registers();
+        mix.slax(3);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [4, 5, 6, 7, 8]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [9, 10, 0, 0, 0]));
+    }
+
+    #[test]
+    fn test_srax() {
+        let mut mix = shift_registers();
+        mix.srax(1);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 1, 2, 3, 4]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [5, 6, 7, 8, 9]));
+        mix.srax(20);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0; 5]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [0; 5]));
+    }
+
+    #[test]
+    fn test_slc() {
+        let mut mix = shift_registers();
+        mix.slc(501);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [2, 3, 4, 5, 6]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [7, 8, 9, 10, 1]));
+    }
+
+    #[test]
+    fn test_src() {
+        let mut mix = shift_registers();
+        mix.src(4);
+        assert_eq!(mix.read_a(), word(Sign::Plus, [7, 8, 9, 10, 1]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [2, 3, 4, 5, 6]));
+    }
+
+    #[test]
+    fn test_shift_sequence_from_the_book() {
+        let mut mix = shift_registers();
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(1, 0, SRAX, SHIFT),
+            Instruction::new(2, 0, SLA, SHIFT),
+            Instruction::new(4, 0, SRC, SHIFT),
+            Instruction::new(2, 0, SRA, SHIFT),
+            Instruction::new(501, 0, SLC, SHIFT),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_a(), word(Sign::Plus, [0, 6, 7, 8, 3]));
+        assert_eq!(mix.read_x(), word(Sign::Minus, [4, 0, 0, 5, 0]));
+    }
+
+    #[test]
+    fn test_invalid_shifts() {
+        let mut mix = shift_registers();
+        assert!(mix.shift(SLA, -1).is_err());
+        assert!(mix.shift(8, 1).is_err());
+    }
+
+    #[test]
+    fn test_enter_and_enter_negative() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_i(0, IndexWord::from_value(-3, b)).unwrap();
+        let program = assemble(&[
+            Instruction::new(2000, 0, ENT, ENA),
+            Instruction::new(5, 1, ENN, ENX),
+            Instruction::new(0, 1, ENT, EN1 + 1),
+            Instruction { sign: Sign::Minus, address: 0, index: 0, field: ENT, opcode: EN1 + 2 },
+            Instruction::new(3, 1, ENT, EN1 + 3),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_a().value(b), 2000);
+        assert_eq!(mix.read_x().value(b), -2);
+        assert_eq!(mix.read_i(1).unwrap().value(b), -3);
+        assert_eq!(mix.read_i(2).unwrap(), IndexWord::new(Sign::Minus, [0, 0]));
+        assert_eq!(mix.read_i(3).unwrap(), IndexWord::ZERO);
+    }
+
+    #[test]
+    fn test_increment_and_decrement() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_a(Word::from_value(10, b));
+        mix.ena(INC, Word::from_value(5, b)).unwrap();
+        assert_eq!(mix.read_a().value(b), 15);
+        mix.enx(DEC, Word::from_value(7, b)).unwrap();
+        assert_eq!(mix.read_x().value(b), -7);
+        mix.eni(4, INC, Word::from_value(100, b)).unwrap();
+        mix.eni(4, DEC, Word::from_value(1, b)).unwrap();
+        assert_eq!(mix.read_i(4).unwrap().value(b), 99);
+        assert!(!mix.get_overflow());
+    }
+
+    #[test]
+    fn test_increment_overflow() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_a(Word::from_value(b.limit(5) - 1, b));
+        mix.ena(INC, Word::from_value(1, b)).unwrap();
+        assert!(mix.get_overflow());
+        assert_eq!(mix.read_a().value(b), 0);
+
+        mix.set_overflow(false);
+        mix.load_i(0, IndexWord::from_value(b.limit(2) - 1, b)).unwrap();
+        assert!(mix.eni(0, INC, Word::from_value(1, b)).is_err());
+        assert!(!mix.get_overflow());
+        assert_eq!(mix.read_i(0).unwrap().value(b), b.limit(2) - 1);
+        assert!(mix.eni(1, ENT, Word::from_value(b.limit(2), b)).is_err());
+    }
+
+    #[test]
+    fn test_subroutine_linkage() {
+        // 0: JMP SUB; 1: HLT
+        // SUB: STJ EXIT(0:2); LDA 100; EXIT: JMP *
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(10, 0, 0, JMP),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        let subroutine = assemble(&[
+            Instruction::new(12, 0, 2, STJ),
+            Instruction::new(100, 0, FULL_FIELD, LDA),
+            Instruction::new(0, 0, 0, JMP),
+        ], b);
+        for (offset, instruction) in subroutine.into_iter().enumerate() {
+            mix.set_memory(10 + offset as i64, instruction).unwrap();
+        }
+        mix.set_memory(100, Word::from_value(77, b)).unwrap();
+
+        mix.run().unwrap();
+        assert_eq!(mix.read_a().value(b), 77);
+        assert_eq!(mix.get_location(), 2);
+        assert_eq!(mix.read_j().value(b), 13);
+    }
+
+    #[test]
+    fn test_jumps_set_j_except_jsj() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.load_program(&assemble(&[
+            Instruction::new(5, 0, JSJ, JMP),
+            Instruction::new(0, 0, 0, 0),
+            Instruction::new(0, 0, 0, 0),
+            Instruction::new(0, 0, 0, 0),
+            Instruction::new(0, 0, 0, 0),
+            Instruction::new(9, 0, JZ, JA),
+            Instruction::new(9, 0, JN, JA),
+        ], b)).unwrap();
+        mix.execute().unwrap();
+        assert_eq!(mix.get_location(), 5);
+        assert_eq!(mix.read_j(), IndexWord::ZERO);
+        mix.execute().unwrap();
+        assert_eq!(mix.get_location(), 9);
+        assert_eq!(mix.read_j().value(b), 6);
+
+        mix.set_location(6).unwrap();
+        mix.execute().unwrap();
+        assert_eq!(mix.get_location(), 7);
+        assert_eq!(mix.read_j().value(b), 6);
+    }
+
+    #[test]
+    fn test_invalid_register_jump() {
+        let mut mix = binary_extensions();
+        assert!(mix.ja(8, 100).is_err());
+    }
+
+    #[test]
+    fn test_comparison_jumps() {
+        let cases = [
+            (Comparison::Less, [true, false, false, false, true, true]),
+            (Comparison::Equal, [false, true, false, true, false, true]),
+            (Comparison::Greater, [false, false, true, true, true, false]),
+        ];
+        for (comparison, expected) in cases {
+            for (jump, taken) in [JL, JE, JG, JGE, JNE, JLE].into_iter().zip(expected) {
+                let mut mix = Mix::new(MixConfig::default());
+                let b = mix.byte_size();
+                mix.load_program(&assemble(&[Instruction::new(100, 0, jump, JMP)], b)).unwrap();
+                mix.set_comparison(comparison);
+                mix.execute().unwrap();
+                let target = if taken { 100 } else { 1 };
+                assert_eq!(mix.get_location(), target, "F={} with {:?}", jump, comparison);
+            }
+        }
+    }
+
+    fn run_sum_program(config: MixConfig) -> Mix {
+        let mut mix = Mix::new(config);
+        let b = mix.byte_size();
+        let program = assemble(&[
+            Instruction::new(100, 0, FULL_FIELD, LDA),
+            Instruction::new(101, 0, FULL_FIELD, ADD),
+            Instruction::new(10, 0, FULL_FIELD, STA),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b);
+        mix.load_program(&program).unwrap();
+        mix.set_memory(100, Word::from_value(63, b)).unwrap();
+        mix.set_memory(101, Word::from_value(1, b)).unwrap();
+        mix.run().unwrap();
+        mix
+    }
+
+    #[test]
+    fn test_same_program_on_binary_and_decimal_machines() {
+        let binary = run_sum_program(MixConfig::binary());
+        let decimal = run_sum_program(MixConfig::decimal());
+
+        assert_eq!(binary.read_memory(10).unwrap().value(ByteSize::Binary), 64);
+        assert_eq!(decimal.read_memory(10).unwrap().value(ByteSize::Decimal), 64);
+        assert_eq!(binary.read_memory(10).unwrap().bytes, [0, 0, 0, 1, 0]);
+        assert_eq!(decimal.read_memory(10).unwrap().bytes, [0, 0, 0, 0, 64]);
+    }
+
+    #[test]
+    fn test_add_wraps_at_the_configured_word_size() {
+        let mut mix = Mix::new(MixConfig::decimal());
+        mix.load_a(Word::from_value(9_999_999_999, ByteSize::Decimal));
+        mix.set_memory(0, Word::from_value(2, ByteSize::Decimal)).unwrap();
+        mix.add(0, FULL_FIELD).unwrap();
+        assert_eq!(mix.read_a().value(ByteSize::Decimal), 1);
     }
 
 }
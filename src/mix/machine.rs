@@ -1,41 +1,203 @@
 // machine.rs
+use std::collections::VecDeque;
+
+use super::device::{Device, UNITS};
+use super::profile::Profile;
+use super::word::{ByteSize, IndexWord, Sign, Word};
+
+// Options that select which variant of the machine is built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MixConfig {
+    pub byte_size: ByteSize,
+    // the floating-point attachment of section 4.2.1 (FADD, FSUB, FMUL,
+    // FDIV, FLOT, FIX and FCMP)
+    pub floating_point: bool,
+    // the binary-only AND, OR, XOR, SLB, SRB, JAE, JAO, JXE and JXO; only
+    // valid with a binary byte size
+    pub binary_extensions: bool,
+    // the interrupt facility of exercise 1.4.4-18: a control state with
+    // memory locations -1 to -3999, the INT instruction and a real-time clock
+    pub interrupts: bool,
+}
+
+impl MixConfig {
+    pub fn binary() -> Self {
+        MixConfig { byte_size: ByteSize::Binary, ..Default::default() }
+    }
+
+    pub fn decimal() -> Self {
+        MixConfig { byte_size: ByteSize::Decimal, ..Default::default() }
+    }
+}
+
+// Locations of the interrupt facility. On an interrupt rA, rI1-rI6 and rX
+// are saved in -9 to -2, and -1 holds the location of the next instruction
+// in (1:2), the overflow toggle and comparison indicator in byte 3 and rJ
+// in (4:5). The timer, INT and unit u interrupt through -11, -12 and -20-u.
+pub const SAVED_A: i64 = -9;
+pub const SAVED_X: i64 = -2;
+pub const SAVED_STATE: i64 = -1;
+pub const CLOCK: i64 = -10;
+pub const TIMER_INTERRUPT: i64 = -11;
+pub const INT_INTERRUPT: i64 = -12;
+
+// time units between two ticks of the real-time clock
+pub const CLOCK_TICK: u64 = 1000;
+
+// The comparison indicator, set by the CMP instructions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Comparison {
+    Less,
+    #[default]
+    Equal,
+    Greater,
+}
+
+impl Comparison {
+    pub fn of(left: i64, right: i64) -> Self {
+        match left.cmp(&right) {
+            std::cmp::Ordering::Less => Comparison::Less,
+            std::cmp::Ordering::Equal => Comparison::Equal,
+            std::cmp::Ordering::Greater => Comparison::Greater,
+        }
+    }
+
+    fn code(self) -> u8 {
+        match self {
+            Comparison::Less => 0,
+            Comparison::Equal => 1,
+            Comparison::Greater => 2,
+        }
+    }
+
+    fn from_code(code: u8) -> Self {
+        match code {
+            0 => Comparison::Less,
+            2 => Comparison::Greater,
+            _ => Comparison::Equal,
+        }
+    }
+}
+
 pub struct Mix {
-    a: i32,
-    x: i32,
-    i: [i32; 6],
-    memory: [i32; 4000],
-    comparison: i32,
+    config: MixConfig,
+    a: Word,
+    x: Word,
+    i: [IndexWord; 6],
+    j: IndexWord,
+    memory: [Word; 4000],
+    // locations -1 to -3999, at index -address - 1
+    control_memory: Vec<Word>,
+    control_state: bool,
+    pending_interrupts: VecDeque<i64>,
+    overflow: bool,
+    comparison: Comparison,
     location: i32,
+    clock: u64,
+    // time since the last tick of the real-time clock
+    tick: u64,
+    profile: Option<Profile>,
+    devices: Vec<Option<Box<dyn Device>>>,
+    // clock time at which each unit finishes its current operation
+    busy_until: [u64; UNITS],
+    // units whose current operation has yet to be reported by an interrupt
+    in_progress: [bool; UNITS],
 }
-use super::instructions::{LDA,STA,ADD,SUB,DIV,JMP,JZ,JL,CMP,HALT};
+use super::instructions::{
+    Instruction, NOP, ADD, SUB, MUL, DIV, SPECIAL, NUM, CHAR, HLT, AND, OR, XOR, FLOT, FIX, FLOATING, SHIFT, MOVE, LDA, LD1, LD6, LDX, LDAN, LD1N, LD6N, LDXN,
+    STA, ST1, ST6, STX, STJ, STZ, JMP, JSJ, JOV, JNOV, JL, JE, JG, JGE, JNE, JLE, JA, J1, J6, JX,
+    ENA, EN1, EN6, ENX, CMPA, CMP1, CMP6, CMPX, INT, JBUS, IOC, IN, OUT, JRED,
+};
 
 impl Mix {
-    pub fn new() -> Self {
+    pub fn new(config: MixConfig) -> Self {
         Mix {
-            a: 0,
-            x: 0,
-            i: [0; 6],
-            memory: [0; 4000],
-            comparison: 0,
+            config,
+            a: Word::ZERO,
+            x: Word::ZERO,
+            i: [IndexWord::ZERO; 6],
+            j: IndexWord::ZERO,
+            memory: [Word::ZERO; 4000],
+            control_memory: if config.interrupts { vec![Word::ZERO; 3999] } else { Vec::new() },
+            control_state: false,
+            pending_interrupts: VecDeque::new(),
+            overflow: false,
+            comparison: Comparison::Equal,
             location: 0,
+            clock: 0,
+            tick: 0,
+            profile: None,
+            devices: (0..UNITS).map(|_| None).collect(),
+            busy_until: [0; UNITS],
+            in_progress: [false; UNITS],
         }
     }
 
+    pub fn config(&self) -> MixConfig {
+        self.config
+    }
+
+    pub fn byte_size(&self) -> ByteSize {
+        self.config.byte_size
+    }
 
-    pub fn get_comparison(&self) -> i32 {
+    pub fn get_overflow(&self) -> bool {
+        self.overflow
+    }
+
+    pub fn set_overflow(&mut self, value: bool) {
+        self.overflow = value;
+    }
+
+    pub fn get_comparison(&self) -> Comparison {
         self.comparison
     }
 
-    pub fn get_location(&self) -> usize {
-        self.location as usize
+    // total execution time so far, in units u
+    pub fn get_clock(&self) -> u64 {
+        self.clock
+    }
+
+    // starts counting executions and time per memory location
+    pub fn enable_profiling(&mut self) {
+        if self.profile.is_none() {
+            self.profile = Some(Profile::new(self.memory.len()));
+        }
+    }
+
+    pub fn disable_profiling(&mut self) {
+        self.profile = None;
+    }
+
+    pub fn profile(&self) -> Option<&Profile> {
+        self.profile.as_ref()
+    }
+
+    pub fn get_location(&self) -> i32 {
+        self.location
     }
 
-    pub fn set_comparison(&mut self, value: i32) {
+    // true while an interrupt is being serviced; only then are the negative
+    // locations addressable
+    pub fn in_control_state(&self) -> bool {
+        self.control_state
+    }
+
+    // whether an instruction may address the location in the current state
+    pub fn accessible(&self, address: i64) -> bool {
+        if address < 0 {
+            self.control_state && address >= -(self.control_memory.len() as i64)
+        } else {
+            address < self.memory.len() as i64
+        }
+    }
+
+    pub fn set_comparison(&mut self, value: Comparison) {
         self.comparison = value;
     }
 
     pub fn set_location(&mut self, address: i32) -> Result<(), &'static str> {
-        if address < 0 || address >= self.memory.len() as i32 {
+        if !self.accessible(address as i64) {
             Err("Memory location out of range")
         } else {
             self.location = address;
@@ -44,12 +206,17 @@ impl Mix {
     }
 
     // load a value in the register A
-    pub fn load_a(&mut self, value: i32) {
+    pub fn load_a(&mut self, value: Word) {
         self.a = value;
     }
 
-    pub fn set_memory(&mut self, address: usize, value: i32) -> Result<(), &'static str> {
-        if let Some(cell) = self.memory.get_mut(address) {
+    pub fn set_memory(&mut self, address: i64, value: Word) -> Result<(), &'static str> {
+        let cell = if address < 0 {
+            self.control_memory.get_mut((-address - 1) as usize)
+        } else {
+            self.memory.get_mut(address as usize)
+        };
+        if let Some(cell) = cell {
             *cell = value;
             Ok(())
         } else {
@@ -57,21 +224,21 @@ impl Mix {
         }
     }
 
-    pub fn read_a(&self) -> i32 {
+    pub fn read_a(&self) -> Word {
         self.a
     }
 
     // load a value in the register X
-    pub fn load_x(&mut self, value: i32) {
+    pub fn load_x(&mut self, value: Word) {
         self.x = value;
     }
 
-    pub fn read_x(&self) -> i32 {
+    pub fn read_x(&self) -> Word {
         self.x
     }
 
     // load a value in the index register i
-    pub fn load_i(&mut self, index: usize, value: i32) -> Result<(), &'static str> {
+    pub fn load_i(&mut self, index: usize, value: IndexWord) -> Result<(), &'static str> {
         if let Some(i) = self.i.get_mut(index) {
             *i = value;
             Ok(())
@@ -80,93 +247,457 @@ impl Mix {
         }
     }
 
-    pub fn read_i(&self, index: usize) -> Option<i32> {
+    pub fn read_i(&self, index: usize) -> Option<IndexWord> {
         self.i.get(index).copied()
     }
 
-    pub fn read_memory(&self, address: usize) -> Option<i32> {
-        self.memory.get(address).copied()
+    // load a value in the register J, whose sign is always +
+    pub fn load_j(&mut self, value: IndexWord) {
+        self.j = IndexWord::new(Sign::Plus, value.bytes);
+    }
+
+    pub fn read_j(&self) -> IndexWord {
+        self.j
+    }
+
+    // any location, regardless of the state; negative locations exist only
+    // with the interrupt facility
+    pub fn read_memory(&self, address: i64) -> Option<Word> {
+        if address < 0 {
+            self.control_memory.get((-address - 1) as usize).copied()
+        } else {
+            self.memory.get(address as usize).copied()
+        }
+    }
+
+    // attaches a device to unit 0-20, replacing any device already there
+    pub fn attach_device(&mut self, unit: u8, device: Box<dyn Device>) -> Result<(), &'static str> {
+        let slot = self.devices.get_mut(unit as usize).ok_or("Invalid unit")?;
+        *slot = Some(device);
+        Ok(())
+    }
+
+    pub fn detach_device(&mut self, unit: u8) -> Option<Box<dyn Device>> {
+        self.devices.get_mut(unit as usize)?.take()
+    }
+
+    pub fn device_mut(&mut self, unit: u8) -> Result<&mut dyn Device, &'static str> {
+        match self.devices.get_mut(unit as usize) {
+            Some(Some(device)) => Ok(device.as_mut()),
+            Some(None) => Err("No device attached to unit"),
+            None => Err("Invalid unit"),
+        }
+    }
+
+    pub fn is_busy(&self, unit: u8) -> bool {
+        self.busy_until.get(unit as usize).is_some_and(|&until| until > self.clock)
+    }
+
+    // stalls until the unit has finished its current operation; the time
+    // spent waiting is charged to the instruction being executed
+    pub fn wait_for_unit(&mut self, unit: u8) {
+        if let Some(&until) = self.busy_until.get(unit as usize) {
+            self.clock = self.clock.max(until);
+        }
     }
 
-    pub fn load_program(&mut self, program: &[i32]) -> Result<(), &'static str> {
+    // keeps the unit busy for the operation time of its device
+    pub fn start_operation(&mut self, unit: u8) -> Result<(), &'static str> {
+        let time = self.device_mut(unit)?.operation_time();
+        if time > 0 {
+            self.busy_until[unit as usize] = self.clock + time;
+            self.in_progress[unit as usize] = true;
+        }
+        Ok(())
+    }
+
+    // with the interrupt facility, a unit finishing an operation interrupts
+    // through -20 - u
+    fn complete_operations(&mut self) -> Result<(), &'static str> {
+        for unit in 0..UNITS {
+            if self.in_progress[unit] && self.busy_until[unit] <= self.clock {
+                self.in_progress[unit] = false;
+                if self.config.interrupts {
+                    self.interrupt(-20 - unit as i64)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn load_program(&mut self, program: &[Word]) -> Result<(), &'static str> {
         if program.len() > self.memory.len() {
             return Err("Program is too large to fit in memory");
         }
         for (i, instruction) in program.iter().enumerate() {
-            self.set_memory(i as usize, *instruction)?;
+            self.set_memory(i as i64, *instruction)?;
         }
 
         Ok(())
     }
 
+    // M = AA + rIi, where I = 0 leaves the address unmodified
+    pub fn effective_address(&self, instruction: &Instruction) -> Result<i64, &'static str> {
+        let offset = match instruction.index {
+            0 => 0,
+            1..=6 => self.i[instruction.index as usize - 1].value(self.byte_size()),
+            _ => return Err("Invalid index register"),
+        };
+        Ok(instruction.address_value() + offset)
+    }
+
+    // M as a word for the address transfer operators; a zero M takes the
+    // sign of the instruction, so that ENTA -0 loads -0
+    fn effective_word(&self, instruction: &Instruction) -> Result<Word, &'static str> {
+        let m = self.effective_address(instruction)?;
+        let mut word = Word::from_value(m, self.byte_size());
+        if m == 0 {
+            word.sign = instruction.sign;
+        }
+        Ok(word)
+    }
+
+    // the memory cell addressed by an instruction
+    fn memory_address(&self, instruction: &Instruction) -> Result<i64, &'static str> {
+        let address = self.effective_address(instruction)?;
+        if !self.accessible(address) {
+            return Err("Effective address out of range");
+        }
+        Ok(address)
+    }
+
+    // fetches, decodes and executes the instruction at the current location;
+    // returns true when the machine halts
     pub fn execute(&mut self) -> Result<bool, &'static str> {
-        let instruction = self.read_memory(self.get_location()).ok_or("Memory address out of range")?;
-        match instruction {
-            HLT => {
-                self.set_location(self.get_location() as i32 + 1);
-                Ok(true)
+        if !self.control_state {
+            if let Some(location) = self.pending_interrupts.pop_front() {
+                self.enter_control_state(location)?;
+            }
+        }
+        let address = self.get_location() as i64;
+        let word = self.read_memory(address).ok_or("Memory address out of range")?;
+        let instruction = Instruction::decode(&word, self.byte_size());
+        self.location += 1;
+        let start = self.clock;
+        let halted = self.dispatch(&instruction)?;
+        self.clock += instruction.time();
+        let time = self.clock - start;
+        if let Some(profile) = self.profile.as_mut() {
+            if address >= 0 {
+                profile.record(address as usize, time);
+            }
+        }
+        if self.config.interrupts {
+            self.advance_real_time_clock(time)?;
+        }
+        self.complete_operations()?;
+        Ok(halted)
+    }
+
+    // requests an interrupt through the location, serviced before the next
+    // instruction executed in normal state
+    pub fn interrupt(&mut self, location: i64) -> Result<(), &'static str> {
+        if !self.config.interrupts {
+            return Err("Interrupt facility is not installed");
+        }
+        if !self.pending_interrupts.contains(&location) {
+            self.pending_interrupts.push_back(location);
+        }
+        Ok(())
+    }
+
+    // the clock at -10 is decreased once every 1000u while it is positive;
+    // reaching zero interrupts through -11
+    fn advance_real_time_clock(&mut self, time: u64) -> Result<(), &'static str> {
+        let b = self.byte_size();
+        self.tick += time;
+        while self.tick >= CLOCK_TICK {
+            self.tick -= CLOCK_TICK;
+            let clock = self.read_memory(CLOCK).ok_or("Memory address out of range")?.value(b);
+            if clock > 0 {
+                self.set_memory(CLOCK, Word::from_value(clock - 1, b))?;
+                if clock == 1 {
+                    self.interrupt(TIMER_INTERRUPT)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // saves the registers and the location of the next instruction in -9 to
+    // -1 and jumps to the location in control state
+    fn enter_control_state(&mut self, location: i64) -> Result<(), &'static str> {
+        let b = self.byte_size();
+        self.set_memory(SAVED_A, self.a)?;
+        for (index, register) in self.i.into_iter().enumerate() {
+            self.set_memory(SAVED_A + 1 + index as i64, register.to_word())?;
+        }
+        self.set_memory(SAVED_X, self.x)?;
+        let next = IndexWord::from_value(self.location as i64, b);
+        let state = 3 * self.overflow as u8 + self.comparison.code();
+        let saved = Word::new(Sign::Plus, [next.bytes[0], next.bytes[1], state, self.j.bytes[0], self.j.bytes[1]]);
+        self.set_memory(SAVED_STATE, saved)?;
+        self.control_state = true;
+        self.location = location as i32;
+        Ok(())
+    }
+
+    // INT: in normal state interrupts through -12; in control state restores
+    // the registers saved in -9 to -1 and returns to normal state
+    pub fn int(&mut self) -> Result<(), &'static str> {
+        if !self.config.interrupts {
+            return Err("Interrupt facility is not installed");
+        }
+        if !self.control_state {
+            return self.enter_control_state(INT_INTERRUPT);
+        }
+        let b = self.byte_size();
+        self.a = self.read_memory(SAVED_A).ok_or("Memory address out of range")?;
+        for index in 0..self.i.len() {
+            let saved = self.read_memory(SAVED_A + 1 + index as i64).ok_or("Memory address out of range")?;
+            self.i[index] = IndexWord::from_word(&saved)?;
+        }
+        self.x = self.read_memory(SAVED_X).ok_or("Memory address out of range")?;
+        let saved = self.read_memory(SAVED_STATE).ok_or("Memory address out of range")?;
+        self.overflow = saved.bytes[2] / 3 == 1;
+        self.comparison = Comparison::from_code(saved.bytes[2] % 3);
+        self.j = IndexWord::new(Sign::Plus, [saved.bytes[3], saved.bytes[4]]);
+        self.location = IndexWord::new(Sign::Plus, [saved.bytes[0], saved.bytes[1]]).value(b) as i32;
+        self.control_state = false;
+        Ok(())
+    }
+
+    fn dispatch(&mut self, instruction: &Instruction) -> Result<bool, &'static str> {
+        let instruction = *instruction;
+        let field = instruction.field;
+        match instruction.opcode {
+            NOP => Ok(false),
+            SPECIAL if field == NUM => {
+                self.num();
+                Ok(false)
+            },
+            SPECIAL if field == CHAR => {
+                self.char();
+                Ok(false)
+            },
+            SPECIAL if field == HLT => Ok(true),
+            SPECIAL if field == INT => {
+                self.int()?;
+                Ok(false)
+            },
+            SPECIAL if field == AND => {
+                self.and(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            SPECIAL if field == OR => {
+                self.or(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            SPECIAL if field == XOR => {
+                self.xor(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            SHIFT => {
+                self.shift(field, self.effective_address(&instruction)?)?;
+                Ok(false)
+            },
+            MOVE => {
+                self.move_words(self.memory_address(&instruction)?, field)?;
+                Ok(false)
             },
             LDA => {
-                self.lda(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+                self.lda(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            LD1..=LD6 => {
+                self.ldi((instruction.opcode - LD1) as usize, self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            LDX => {
+                self.ldx(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            LDAN => {
+                self.ldan(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            LD1N..=LD6N => {
+                self.ldin((instruction.opcode - LD1N) as usize, self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            LDXN => {
+                self.ldxn(self.memory_address(&instruction)?, field)?;
                 Ok(false)
             },
             STA => {
-                let address = self.read_memory(self.get_location() + 1).ok_or("Memory address out of range")?;
-                self.sta(address as usize);
-                self.set_location(self.get_location() as i32 + 1);
+                self.sta(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            ST1..=ST6 => {
+                self.sti((instruction.opcode - ST1) as usize, self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            STX => {
+                self.stx(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            STJ => {
+                self.stj(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            STZ => {
+                self.stz(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            ADD if field == FLOATING => {
+                self.fadd(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            SUB if field == FLOATING => {
+                self.fsub(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            MUL if field == FLOATING => {
+                self.fmul(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            DIV if field == FLOATING => {
+                self.fdiv(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            SPECIAL if field == FLOT => {
+                self.flot()?;
+                Ok(false)
+            },
+            SPECIAL if field == FIX => {
+                self.fix()?;
+                Ok(false)
+            },
+            CMPA if field == FLOATING => {
+                self.fcmp(self.memory_address(&instruction)?)?;
                 Ok(false)
             },
             ADD => {
-                self.add(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+                self.add(self.memory_address(&instruction)?, field)?;
                 Ok(false)
             },
             SUB => {
-                self.sub(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+                self.sub(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            MUL => {
+                self.mul(self.memory_address(&instruction)?, field)?;
                 Ok(false)
             },
             DIV => {
-                self.div(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+                self.div(self.memory_address(&instruction)?, field)?;
                 Ok(false)
             },
-            JMP => {
-                self.jmp(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+            JBUS => {
+                self.jbus(field, self.memory_address(&instruction)?)?;
                 Ok(false)
             },
-            JZ => {
-                self.jz(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+            IOC => {
+                self.ioc(field, self.effective_address(&instruction)?)?;
                 Ok(false)
             },
-            JL => {
-                self.jl(self.get_location() + 1);
-                self.set_location(self.get_location() as i32 + 1);
+            IN => {
+                self.input(field, self.memory_address(&instruction)?)?;
                 Ok(false)
             },
-            CMP => {
-                self.cmp(self.get_location() + 1)?;
-                self.set_location(self.get_location() as i32 + 1);
+            OUT => {
+                self.output(field, self.memory_address(&instruction)?)?;
                 Ok(false)
             },
-            _ => {
-                println!("Unkown instruction {}", instruction);
-                Err("Unknown instruction")
-            }
+            JRED => {
+                self.jred(field, self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == 0 => {
+                self.jmp(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JSJ => {
+                self.jsj(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JOV => {
+                self.jov(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JNOV => {
+                self.jnov(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JL => {
+                self.jl(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JE => {
+                self.je(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JG => {
+                self.jg(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JGE => {
+                self.jge(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JNE => {
+                self.jne(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JMP if field == JLE => {
+                self.jle(self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JA => {
+                self.ja(field, self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            J1..=J6 => {
+                self.ji((instruction.opcode - J1) as usize, field, self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            JX => {
+                self.jx(field, self.memory_address(&instruction)?)?;
+                Ok(false)
+            },
+            ENA => {
+                self.ena(field, self.effective_word(&instruction)?)?;
+                Ok(false)
+            },
+            EN1..=EN6 => {
+                self.eni((instruction.opcode - EN1) as usize, field, self.effective_word(&instruction)?)?;
+                Ok(false)
+            },
+            ENX => {
+                self.enx(field, self.effective_word(&instruction)?)?;
+                Ok(false)
+            },
+            CMPA => {
+                self.cmpa(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            CMP1..=CMP6 => {
+                self.cmpi((instruction.opcode - CMP1) as usize, self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            CMPX => {
+                self.cmpx(self.memory_address(&instruction)?, field)?;
+                Ok(false)
+            },
+            _ => Err("Unknown instruction"),
         }
     }
 
-    pub fn run(&mut self) -> Result<(), &'static str> {
-        loop {
-            if self.execute()? {
-                break;
-            }
-            self.set_location(self.get_location() as i32 + 1)?;
-        }
-        Ok(())
+    // runs until HLT and returns the time the run took, in units u
+    pub fn run(&mut self) -> Result<u64, &'static str> {
+        let start = self.clock;
+        while !self.execute()? {}
+        Ok(self.clock - start)
     }
 
     pub fn display_memory(&self) {
@@ -176,20 +707,26 @@ impl Mix {
                 println!();
                 print!("{:04}: ", i);
             }
-            print!("{:05} ", word);
+            print!("{} ", word);
         }
         println!();
     }
 
     pub fn display_registers(&self) {
         println!("Registers:");
-        println!("A: {:05}", self.a);
-        println!("X: {:05}", self.x);
-        println!("Comparison: {}", self.comparison);
+        println!("A: {}", self.a);
+        println!("X: {}", self.x);
+        println!("Overflow: {}", if self.overflow { "ON" } else { "OFF" });
+        println!("Comparison: {:?}", self.comparison);
         println!("Location: {}", self.location);
+        if self.config.interrupts {
+            println!("State: {}", if self.control_state { "CONTROL" } else { "NORMAL" });
+        }
+        println!("Clock: {}u", self.clock);
         for (i, register) in self.i.iter().enumerate() {
-            println!("I{}: {:05}", i + 1, register);
+            println!("I{}: {}", i + 1, register);
         }
+        println!("J: {}", self.j);
     }
 }
 
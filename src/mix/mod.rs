@@ -1,3 +1,14 @@
 // mod.rs
 pub mod machine;
 pub mod instructions;
+pub mod word;
+pub mod card;
+pub mod charset;
+pub mod device;
+pub mod disk;
+pub mod float;
+pub mod paper_tape;
+pub mod printer;
+pub mod profile;
+pub mod tape;
+pub mod typewriter;
@@ -0,0 +1,88 @@
+// paper_tape.rs
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+use super::device::{read_text_block, Device};
+use super::word::Word;
+
+// 70 characters to a line
+pub const PAPER_TAPE_BLOCK: usize = 14;
+
+// The paper tape reader, unit 20: each IN reads the next line of the tape,
+// such as a host file, and IOC 0 rewinds it.
+pub struct PaperTape<R: BufRead + Seek> {
+    input: R,
+}
+
+impl<R: BufRead + Seek> PaperTape<R> {
+    pub fn new(input: R) -> Self {
+        PaperTape { input }
+    }
+}
+
+impl PaperTape<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let file = File::open(path).map_err(|_| "Cannot open paper tape")?;
+        Ok(PaperTape::new(BufReader::new(file)))
+    }
+}
+
+impl<R: BufRead + Seek> Device for PaperTape<R> {
+    fn block_size(&self) -> usize {
+        PAPER_TAPE_BLOCK
+    }
+
+    fn read_block(&mut self, _position: i64) -> Result<Vec<Word>, &'static str> {
+        read_text_block(&mut self.input, PAPER_TAPE_BLOCK, "Cannot read paper tape")?.ok_or("End of paper tape")
+    }
+
+    // IOC 0 rewinds the tape
+    fn control(&mut self, m: i64, _position: i64) -> Result<(), &'static str> {
+        if m != 0 {
+            return Err("Invalid paper tape control");
+        }
+        self.input.seek(SeekFrom::Start(0)).map_err(|_| "Cannot rewind paper tape")?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mix::device::{block_to_text, temp_path, PAPER_TAPE};
+    use crate::mix::instructions::{assemble, Instruction, HLT, IN, IOC, SPECIAL};
+    use crate::mix::machine::{Mix, MixConfig};
+    use std::io::Cursor;
+
+    #[test]
+    fn test_read_and_rewind() {
+        let mut tape = PaperTape::new(Cursor::new("FIRST\nSECOND\n"));
+        assert_eq!(block_to_text(&tape.read_block(0).unwrap()).unwrap(), "FIRST");
+        assert_eq!(block_to_text(&tape.read_block(0).unwrap()).unwrap(), "SECOND");
+        assert_eq!(tape.read_block(0), Err("End of paper tape"));
+        tape.control(0, 0).unwrap();
+        assert_eq!(block_to_text(&tape.read_block(0).unwrap()).unwrap(), "FIRST");
+        assert_eq!(tape.control(1, 0), Err("Invalid paper tape control"));
+        assert!(tape.write_block(0, &[Word::ZERO; PAPER_TAPE_BLOCK]).is_err());
+    }
+
+    #[test]
+    fn test_read_from_a_host_file() {
+        let path = temp_path("paper-tape");
+        std::fs::write(&path, "0123456789\n").unwrap();
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.attach_device(PAPER_TAPE, Box::new(PaperTape::open(&path).unwrap())).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, PAPER_TAPE, IN),
+            Instruction::new(0, 0, PAPER_TAPE, IOC),
+            Instruction::new(200, 0, PAPER_TAPE, IN),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_memory(201).unwrap().bytes, [35, 36, 37, 38, 39]);
+        assert_eq!(mix.read_memory(100), mix.read_memory(200));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
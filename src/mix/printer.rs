@@ -0,0 +1,105 @@
+// printer.rs
+use std::fs::File;
+use std::io::{self, Stdout, Write};
+use std::path::Path;
+
+use super::device::{block_to_text, Device};
+use super::word::Word;
+
+// 120 characters to a line
+pub const PRINTER_BLOCK: usize = 24;
+
+// character written for a page eject
+pub const FORM_FEED: char = '\u{c}';
+
+// The line printer, unit 18: each OUT prints one line of text and IOC 0
+// ejects the page.
+pub struct LinePrinter<W: Write> {
+    output: W,
+}
+
+impl<W: Write> LinePrinter<W> {
+    pub fn new(output: W) -> Self {
+        LinePrinter { output }
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+}
+
+impl LinePrinter<File> {
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let file = File::create(path).map_err(|_| "Cannot create printer output")?;
+        Ok(LinePrinter::new(file))
+    }
+}
+
+impl LinePrinter<Stdout> {
+    pub fn stdout() -> Self {
+        LinePrinter::new(io::stdout())
+    }
+}
+
+impl<W: Write> Device for LinePrinter<W> {
+    fn block_size(&self) -> usize {
+        PRINTER_BLOCK
+    }
+
+    fn write_block(&mut self, _position: i64, block: &[Word]) -> Result<(), &'static str> {
+        let line = block_to_text(block)?;
+        writeln!(self.output, "{}", line).map_err(|_| "Cannot write printer output")
+    }
+
+    // IOC 0 skips to the top of the next page
+    fn control(&mut self, m: i64, _position: i64) -> Result<(), &'static str> {
+        if m != 0 {
+            return Err("Invalid printer control");
+        }
+        write!(self.output, "{}", FORM_FEED).map_err(|_| "Cannot write printer output")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mix::charset;
+    use crate::mix::device::{SharedOutput, PRINTER};
+    use crate::mix::instructions::{assemble, Instruction, CHAR, HLT, IOC, OUT, SPECIAL, STA, STX};
+    use crate::mix::machine::{Mix, MixConfig};
+    use crate::mix::word::Sign;
+
+    #[test]
+    fn test_lines_and_page_eject() {
+        let mut printer = LinePrinter::new(Vec::new());
+        let mut line = vec![Word::ZERO; PRINTER_BLOCK];
+        line[0] = Word::new(Sign::Plus, [8, 5, 13, 13, 16]);
+        line[23] = Word::new(Sign::Plus, [0, 0, 0, 0, charset::from_char('.').unwrap()]);
+        printer.write_block(0, &line).unwrap();
+        printer.control(0, 0).unwrap();
+        printer.write_block(0, &[Word::ZERO; PRINTER_BLOCK]).unwrap();
+        let text = String::from_utf8(printer.output().clone()).unwrap();
+        assert_eq!(text, format!("HELLO{}.\n\u{c}\n", " ".repeat(114)));
+        assert_eq!(printer.control(1, 0), Err("Invalid printer control"));
+    }
+
+    #[test]
+    fn test_print_a_number() {
+        // converts rA to characters with CHAR and prints them
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let output = SharedOutput::default();
+        mix.attach_device(PRINTER, Box::new(LinePrinter::new(output.clone()))).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(0, 0, CHAR, SPECIAL),
+            Instruction::new(1000, 0, 5, STA),
+            Instruction::new(1001, 0, 5, STX),
+            Instruction::new(0, 0, PRINTER, IOC),
+            Instruction::new(1000, 0, PRINTER, OUT),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.load_a(Word::from_value(1234, b));
+        mix.run().unwrap();
+        assert_eq!(output.text(), "\u{c}0000001234\n");
+    }
+}
@@ -0,0 +1,93 @@
+// profile.rs
+use std::fmt;
+
+// Knuth-style execution profile: how many times the instruction at each
+// memory location was executed and how much time it consumed in total.
+pub struct Profile {
+    counts: Vec<u64>,
+    times: Vec<u64>,
+}
+
+impl Profile {
+    pub fn new(size: usize) -> Self {
+        Profile {
+            counts: vec![0; size],
+            times: vec![0; size],
+        }
+    }
+
+    pub fn record(&mut self, address: usize, time: u64) {
+        if let (Some(count), Some(total)) = (self.counts.get_mut(address), self.times.get_mut(address)) {
+            *count += 1;
+            *total += time;
+        }
+    }
+
+    pub fn count(&self, address: usize) -> u64 {
+        self.counts.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn time(&self, address: usize) -> u64 {
+        self.times.get(address).copied().unwrap_or(0)
+    }
+
+    pub fn total_time(&self) -> u64 {
+        self.times.iter().sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.times.fill(0);
+    }
+
+    // the count and time columns for one location, to be printed in front
+    // of its line in a source listing; blank for locations never executed
+    pub fn entry(&self, address: usize) -> String {
+        match self.count(address) {
+            0 => format!("{:>8} {:>9}", "", ""),
+            count => format!("{:>8} {:>8}u", count, self.time(address)),
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>4} {:>8} {:>9}", "LOC", "COUNT", "TIME")?;
+        for address in 0..self.counts.len() {
+            if self.count(address) > 0 {
+                writeln!(f, "{:04} {}", address, self.entry(address))?;
+            }
+        }
+        write!(f, "Total time: {}u", self.total_time())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_accumulates_per_address() {
+        let mut profile = Profile::new(10);
+        profile.record(3, 2);
+        profile.record(3, 2);
+        profile.record(4, 10);
+        profile.record(20, 1);
+        assert_eq!(profile.count(3), 2);
+        assert_eq!(profile.time(3), 4);
+        assert_eq!(profile.count(5), 0);
+        assert_eq!(profile.total_time(), 14);
+    }
+
+    #[test]
+    fn test_report_lists_executed_locations() {
+        let mut profile = Profile::new(10);
+        profile.record(1, 2);
+        profile.record(1, 2);
+        let report = profile.to_string();
+        assert!(report.contains("0001        2        4u"));
+        assert!(!report.contains("0002"));
+        assert!(report.ends_with("Total time: 4u"));
+        assert_eq!(profile.entry(0).trim(), "");
+    }
+}
@@ -0,0 +1,214 @@
+// tape.rs
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::device::{block_from_bytes, block_to_bytes, Device, WORD_BYTES};
+use super::word::Word;
+
+pub const TAPE_BLOCK: usize = 100;
+
+const BLOCK_BYTES: u64 = (TAPE_BLOCK * WORD_BYTES) as u64;
+
+// A magnetic tape unit recorded in a host file, one block after another, so
+// that what a program writes is still there for the next run. Writing a
+// block erases the rest of the tape, as on a real drive; reading past the
+// last block or writing past the capacity is an end-of-tape error.
+pub struct Tape {
+    file: File,
+    // index of the block under the read/write head
+    position: u64,
+    capacity: u64,
+    operation_time: u64,
+}
+
+impl Tape {
+    // opens the tape in the file, creating an empty one if needed, with the
+    // head at the load point
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, &'static str> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(|_| "Cannot open tape file")?;
+        Ok(Tape { file, position: 0, capacity: u64::MAX, operation_time: 0 })
+    }
+
+    // limits the tape to a number of blocks
+    pub fn with_capacity(mut self, blocks: u64) -> Self {
+        self.capacity = blocks;
+        self
+    }
+
+    // the time in units u that the unit stays busy after each operation
+    pub fn with_operation_time(mut self, time: u64) -> Self {
+        self.operation_time = time;
+        self
+    }
+
+    // number of blocks recorded on the tape
+    pub fn len(&self) -> Result<u64, &'static str> {
+        let bytes = self.file.metadata().map_err(|_| "Cannot read tape file")?.len();
+        Ok(bytes / BLOCK_BYTES)
+    }
+
+    pub fn is_empty(&self) -> Result<bool, &'static str> {
+        Ok(self.len()? == 0)
+    }
+
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    // true when the head is past the last recorded block
+    pub fn at_end(&self) -> Result<bool, &'static str> {
+        Ok(self.position >= self.len()?)
+    }
+}
+
+impl Device for Tape {
+    fn block_size(&self) -> usize {
+        TAPE_BLOCK
+    }
+
+    fn read_block(&mut self, _position: i64) -> Result<Vec<Word>, &'static str> {
+        if self.at_end()? {
+            return Err("End of tape");
+        }
+        let mut bytes = vec![0; BLOCK_BYTES as usize];
+        self.file.seek(SeekFrom::Start(self.position * BLOCK_BYTES)).map_err(|_| "Cannot read tape file")?;
+        self.file.read_exact(&mut bytes).map_err(|_| "Cannot read tape file")?;
+        self.position += 1;
+        block_from_bytes(&bytes)
+    }
+
+    fn write_block(&mut self, _position: i64, block: &[Word]) -> Result<(), &'static str> {
+        if self.position >= self.capacity {
+            return Err("End of tape");
+        }
+        let offset = self.position * BLOCK_BYTES;
+        self.file.seek(SeekFrom::Start(offset)).map_err(|_| "Cannot write tape file")?;
+        self.file.write_all(&block_to_bytes(block)).map_err(|_| "Cannot write tape file")?;
+        self.file.set_len(offset + BLOCK_BYTES).map_err(|_| "Cannot write tape file")?;
+        self.position += 1;
+        Ok(())
+    }
+
+    // IOC 0 rewinds; M < 0 skips back -M blocks, stopping at the load
+    // point, and M > 0 skips forward M blocks
+    fn control(&mut self, m: i64, _position: i64) -> Result<(), &'static str> {
+        if m == 0 {
+            self.position = 0;
+        } else if m < 0 {
+            self.position = self.position.saturating_sub(m.unsigned_abs());
+        } else {
+            let position = self.position + m as u64;
+            if position > self.len()? {
+                return Err("End of tape");
+            }
+            self.position = position;
+        }
+        Ok(())
+    }
+
+    fn operation_time(&self) -> u64 {
+        self.operation_time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mix::device::{temp_path, TAPES};
+    use crate::mix::instructions::{assemble, Instruction, HLT, IN, IOC, OUT, SPECIAL};
+    use crate::mix::machine::{Mix, MixConfig};
+    use crate::mix::word::Sign;
+
+    fn block(value: u8) -> Vec<Word> {
+        vec![Word::new(Sign::Minus, [0, 0, 0, 0, value]); TAPE_BLOCK]
+    }
+
+    #[test]
+    fn test_write_rewind_and_read() {
+        let path = temp_path("tape-rewind");
+        let mut tape = Tape::open(&path).unwrap();
+        for value in 1..=3 {
+            tape.write_block(0, &block(value)).unwrap();
+        }
+        assert_eq!(tape.len(), Ok(3));
+        assert!(tape.at_end().unwrap());
+        assert_eq!(tape.read_block(0), Err("End of tape"));
+
+        tape.control(0, 0).unwrap();
+        assert_eq!(tape.read_block(0).unwrap(), block(1));
+        tape.control(1, 0).unwrap();
+        assert_eq!(tape.read_block(0).unwrap(), block(3));
+        tape.control(-2, 0).unwrap();
+        assert_eq!(tape.read_block(0).unwrap(), block(2));
+        tape.control(-10, 0).unwrap();
+        assert_eq!(tape.position(), 0);
+        assert_eq!(tape.control(4, 0), Err("End of tape"));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_writing_erases_the_rest_of_the_tape() {
+        let path = temp_path("tape-erase");
+        let mut tape = Tape::open(&path).unwrap().with_capacity(3);
+        for value in 1..=3 {
+            tape.write_block(0, &block(value)).unwrap();
+        }
+        assert_eq!(tape.write_block(0, &block(4)), Err("End of tape"));
+        tape.control(-2, 0).unwrap();
+        tape.write_block(0, &block(5)).unwrap();
+        assert_eq!(tape.len(), Ok(2));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_contents_persist_between_runs() {
+        let path = temp_path("tape-persist");
+        let mut tape = Tape::open(&path).unwrap();
+        tape.write_block(0, &block(7)).unwrap();
+        drop(tape);
+
+        let mut tape = Tape::open(&path).unwrap();
+        assert_eq!(tape.position(), 0);
+        assert_eq!(tape.read_block(0).unwrap(), block(7));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_merge_pass_through_the_machine() {
+        // writes two blocks on one tape and copies them to the next
+        let (first, second) = (temp_path("tape-first"), temp_path("tape-second"));
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let units = [*TAPES.start(), *TAPES.start() + 1];
+        mix.attach_device(units[0], Box::new(Tape::open(&first).unwrap())).unwrap();
+        mix.attach_device(units[1], Box::new(Tape::open(&second).unwrap())).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(1000, 0, units[0], OUT),
+            Instruction::new(1100, 0, units[0], OUT),
+            Instruction::new(0, 0, units[0], IOC),
+            Instruction::new(2000, 0, units[0], IN),
+            Instruction::new(2000, 0, units[1], OUT),
+            Instruction::new(2000, 0, units[0], IN),
+            Instruction::new(2000, 0, units[1], OUT),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.set_memory(1000, Word::from_value(1, b)).unwrap();
+        mix.set_memory(1100, Word::from_value(2, b)).unwrap();
+        mix.run().unwrap();
+        drop(mix);
+
+        let mut copy = Tape::open(&second).unwrap();
+        assert_eq!(copy.read_block(0).unwrap()[0].value(b), 1);
+        assert_eq!(copy.read_block(0).unwrap()[0].value(b), 2);
+        assert!(copy.at_end().unwrap());
+        std::fs::remove_file(&first).unwrap();
+        std::fs::remove_file(&second).unwrap();
+    }
+}
@@ -0,0 +1,120 @@
+// typewriter.rs
+use std::io::{self, Stdout, Write};
+
+use super::device::{block_to_text, read_text_block, Device, LineInput, StdinInput};
+use super::word::Word;
+
+// 70 characters to a line
+pub const TYPEWRITER_BLOCK: usize = 14;
+
+// The typewriter terminal, unit 19: IN waits for the operator to type a
+// line and OUT types one.
+pub struct Typewriter<R: LineInput, W: Write> {
+    input: R,
+    output: W,
+}
+
+impl<R: LineInput, W: Write> Typewriter<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Typewriter { input, output }
+    }
+
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+}
+
+impl Typewriter<StdinInput, Stdout> {
+    // the typewriter on the host terminal
+    pub fn terminal() -> Self {
+        Typewriter::new(StdinInput, io::stdout())
+    }
+}
+
+impl<R: LineInput, W: Write> Device for Typewriter<R, W> {
+    fn block_size(&self) -> usize {
+        TYPEWRITER_BLOCK
+    }
+
+    fn read_block(&mut self, _position: i64) -> Result<Vec<Word>, &'static str> {
+        // anything typed so far is shown before the operator answers
+        self.output.flush().map_err(|_| "Cannot write typewriter output")?;
+        read_text_block(&mut self.input, TYPEWRITER_BLOCK, "Cannot read typewriter input")?.ok_or("End of typewriter input")
+    }
+
+    fn write_block(&mut self, _position: i64, block: &[Word]) -> Result<(), &'static str> {
+        let line = block_to_text(block)?;
+        writeln!(self.output, "{}", line).map_err(|_| "Cannot write typewriter output")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mix::card::CardReader;
+    use crate::mix::device::{SharedOutput, CARD_READER, TYPEWRITER};
+    use crate::mix::instructions::{assemble, Instruction, HLT, IN, OUT, SPECIAL};
+    use crate::mix::machine::{Mix, MixConfig};
+    use std::cell::RefCell;
+    use std::io::{BufRead, Cursor};
+    use std::rc::Rc;
+
+    #[derive(Clone)]
+    struct SharedInput(Rc<RefCell<Cursor<String>>>);
+
+    impl LineInput for SharedInput {
+        fn next_line(&mut self, line: &mut String) -> io::Result<usize> {
+            self.0.borrow_mut().read_line(line)
+        }
+    }
+
+    #[test]
+    fn test_echo_a_line() {
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        let output = SharedOutput::default();
+        let input = Cursor::new("what is your name\nknuth\n".to_string());
+        mix.attach_device(TYPEWRITER, Box::new(Typewriter::new(input, output.clone()))).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, TYPEWRITER, IN),
+            Instruction::new(100, 0, TYPEWRITER, OUT),
+            Instruction::new(200, 0, TYPEWRITER, IN),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(output.text(), "WHAT IS YOUR NAME\n");
+        assert_eq!(mix.read_memory(200).unwrap().bytes, [12, 15, 24, 23, 8]);
+    }
+
+    #[test]
+    fn test_reads_interleave_with_the_card_reader() {
+        // both devices take their lines from one input, as from stdin
+        let input = SharedInput(Rc::new(RefCell::new(Cursor::new("CARD ONE\nTYPED\nCARD TWO\n".to_string()))));
+        let mut mix = Mix::new(MixConfig::default());
+        let b = mix.byte_size();
+        mix.attach_device(CARD_READER, Box::new(CardReader::new(input.clone()))).unwrap();
+        mix.attach_device(TYPEWRITER, Box::new(Typewriter::new(input, Vec::new()))).unwrap();
+        mix.load_program(&assemble(&[
+            Instruction::new(100, 0, CARD_READER, IN),
+            Instruction::new(200, 0, TYPEWRITER, IN),
+            Instruction::new(300, 0, CARD_READER, IN),
+            Instruction::new(0, 0, HLT, SPECIAL),
+        ], b)).unwrap();
+        mix.run().unwrap();
+        assert_eq!(mix.read_memory(101).unwrap().bytes, [16, 15, 5, 0, 0]);
+        assert_eq!(mix.read_memory(200).unwrap().bytes, [23, 28, 17, 5, 4]);
+        assert_eq!(mix.read_memory(301).unwrap().bytes, [23, 26, 16, 0, 0]);
+    }
+
+    #[test]
+    fn test_lines_are_converted_both_ways() {
+        let input = Cursor::new("HELLO, MIX.\n".to_string());
+        let mut typewriter = Typewriter::new(input, Vec::new());
+        let line = typewriter.read_block(0).unwrap();
+        assert_eq!(line.len(), TYPEWRITER_BLOCK);
+        typewriter.write_block(0, &line).unwrap();
+        assert_eq!(typewriter.output(), b"HELLO, MIX.\n");
+        assert_eq!(typewriter.read_block(0), Err("End of typewriter input"));
+        assert!(typewriter.control(0, 0).is_err());
+    }
+}
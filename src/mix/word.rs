@@ -0,0 +1,372 @@
+// word.rs
+use std::fmt;
+
+// Knuth leaves the byte size open: a byte holds at least 64 and at most
+// 100 values, and MIX programs are expected to work with either.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ByteSize {
+    #[default]
+    Binary,
+    Decimal,
+}
+
+impl ByteSize {
+    // number of distinct values a single byte can hold
+    pub fn base(self) -> i64 {
+        match self {
+            ByteSize::Binary => 64,
+            ByteSize::Decimal => 100,
+        }
+    }
+
+    // smallest magnitude that no longer fits in the given number of bytes
+    pub fn limit(self, bytes: u32) -> i64 {
+        self.base().pow(bytes)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Sign {
+    #[default]
+    Plus,
+    Minus,
+}
+
+impl Sign {
+    pub fn of(value: i64) -> Self {
+        if value < 0 {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        }
+    }
+
+    pub fn negate(self) -> Self {
+        match self {
+            Sign::Plus => Sign::Minus,
+            Sign::Minus => Sign::Plus,
+        }
+    }
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sign::Plus => write!(f, "+"),
+            Sign::Minus => write!(f, "-"),
+        }
+    }
+}
+
+// A field specification (L:R) selecting the sign (position 0) and/or bytes
+// L..R of a word; instructions encode it in their F byte as 8L + R.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub left: usize,
+    pub right: usize,
+}
+
+impl Field {
+    pub const FULL: Field = Field { left: 0, right: 5 };
+
+    pub fn new(left: usize, right: usize) -> Result<Self, &'static str> {
+        if left > right || right > 5 {
+            return Err("Invalid field specification");
+        }
+        Ok(Field { left, right })
+    }
+
+    pub fn decode(spec: u8) -> Result<Self, &'static str> {
+        Field::new(spec as usize / 8, spec as usize % 8)
+    }
+
+    pub fn encode(&self) -> u8 {
+        (8 * self.left + self.right) as u8
+    }
+
+    pub fn includes_sign(&self) -> bool {
+        self.left == 0
+    }
+
+    // positions 1..=5 of the bytes covered by the field
+    fn byte_positions(&self) -> std::ops::RangeInclusive<usize> {
+        self.left.max(1)..=self.right
+    }
+}
+
+// A full MIX word: a sign and five bytes, byte 1 being the most significant.
+// +0 and -0 are different words, as in the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Word {
+    pub sign: Sign,
+    pub bytes: [u8; 5],
+}
+
+impl Word {
+    pub const ZERO: Word = Word { sign: Sign::Plus, bytes: [0; 5] };
+
+    pub fn new(sign: Sign, bytes: [u8; 5]) -> Self {
+        Word { sign, bytes }
+    }
+
+    // builds a word from an integer, keeping only the five low bytes of the magnitude
+    pub fn from_value(value: i64, byte_size: ByteSize) -> Self {
+        Word {
+            sign: Sign::of(value),
+            bytes: to_bytes(value.unsigned_abs(), byte_size),
+        }
+    }
+
+    pub fn value(&self, byte_size: ByteSize) -> i64 {
+        signed(self.sign, self.magnitude(byte_size))
+    }
+
+    pub fn magnitude(&self, byte_size: ByteSize) -> i64 {
+        from_bytes(&self.bytes, byte_size)
+    }
+
+    // true when the magnitude of the value needs more than five bytes
+    pub fn overflows(value: i64, byte_size: ByteSize) -> bool {
+        value.unsigned_abs() >= byte_size.limit(5) as u64
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.bytes.iter().all(|&b| b == 0)
+    }
+
+    pub fn negate(&self) -> Self {
+        Word { sign: self.sign.negate(), bytes: self.bytes }
+    }
+
+    pub fn with_sign(&self, sign: Sign) -> Self {
+        Word { sign, bytes: self.bytes }
+    }
+
+    // the contents of the field, shifted into the low bytes; the sign is
+    // positive unless the field includes it
+    pub fn field(&self, field: Field) -> Word {
+        let mut result = Word::ZERO;
+        if field.includes_sign() {
+            result.sign = self.sign;
+        }
+        let positions = field.byte_positions();
+        let count = positions.clone().count();
+        for (offset, position) in positions.enumerate() {
+            result.bytes[5 - count + offset] = self.bytes[position - 1];
+        }
+        result
+    }
+
+    // replaces the field with the low bytes (and the sign) of the source word
+    pub fn set_field(&mut self, field: Field, source: &Word) {
+        if field.includes_sign() {
+            self.sign = source.sign;
+        }
+        let positions = field.byte_positions();
+        let count = positions.clone().count();
+        for (offset, position) in positions.enumerate() {
+            self.bytes[position - 1] = source.bytes[5 - count + offset];
+        }
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.sign)?;
+        for byte in self.bytes.iter() {
+            write!(f, " {:02}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+// rA and rX taken together as a ten-byte magnitude, rA holding the high bytes
+pub fn double_magnitude(high: &Word, low: &Word, byte_size: ByteSize) -> u128 {
+    let limit = byte_size.limit(5) as u128;
+    high.magnitude(byte_size) as u128 * limit + low.magnitude(byte_size) as u128
+}
+
+// splits a ten-byte magnitude into its high and low words' bytes
+pub fn split_double(magnitude: u128, byte_size: ByteSize) -> ([u8; 5], [u8; 5]) {
+    let limit = byte_size.limit(5) as u128;
+    let high = to_bytes((magnitude / limit) as u64, byte_size);
+    let low = to_bytes((magnitude % limit) as u64, byte_size);
+    (high, low)
+}
+
+// The two-byte words held by the index registers I1-I6 and by J.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct IndexWord {
+    pub sign: Sign,
+    pub bytes: [u8; 2],
+}
+
+impl IndexWord {
+    pub const ZERO: IndexWord = IndexWord { sign: Sign::Plus, bytes: [0; 2] };
+
+    pub fn new(sign: Sign, bytes: [u8; 2]) -> Self {
+        IndexWord { sign, bytes }
+    }
+
+    pub fn from_value(value: i64, byte_size: ByteSize) -> Self {
+        IndexWord {
+            sign: Sign::of(value),
+            bytes: to_bytes(value.unsigned_abs(), byte_size),
+        }
+    }
+
+    pub fn value(&self, byte_size: ByteSize) -> i64 {
+        signed(self.sign, from_bytes(&self.bytes, byte_size))
+    }
+
+    // true when the magnitude of the value needs more than two bytes
+    pub fn overflows(value: i64, byte_size: ByteSize) -> bool {
+        value.unsigned_abs() >= byte_size.limit(2) as u64
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.bytes.iter().all(|&b| b == 0)
+    }
+
+    // the full word with bytes 1, 2 and 3 set to zero
+    pub fn to_word(self) -> Word {
+        Word::new(self.sign, [0, 0, 0, self.bytes[0], self.bytes[1]])
+    }
+
+    // only succeeds when bytes 1, 2 and 3 of the word are zero
+    pub fn from_word(word: &Word) -> Result<Self, &'static str> {
+        if word.bytes[..3].iter().any(|&b| b != 0) {
+            return Err("Value does not fit in an index register");
+        }
+        Ok(IndexWord::new(word.sign, [word.bytes[3], word.bytes[4]]))
+    }
+}
+
+impl fmt::Display for IndexWord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:02} {:02}", self.sign, self.bytes[0], self.bytes[1])
+    }
+}
+
+fn signed(sign: Sign, magnitude: i64) -> i64 {
+    match sign {
+        Sign::Plus => magnitude,
+        Sign::Minus => -magnitude,
+    }
+}
+
+fn to_bytes<const N: usize>(mut magnitude: u64, byte_size: ByteSize) -> [u8; N] {
+    let base = byte_size.base() as u64;
+    let mut bytes = [0; N];
+    for byte in bytes.iter_mut().rev() {
+        *byte = (magnitude % base) as u8;
+        magnitude /= base;
+    }
+    bytes
+}
+
+fn from_bytes(bytes: &[u8], byte_size: ByteSize) -> i64 {
+    bytes.iter().fold(0, |acc, &b| acc * byte_size.base() + b as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BINARY: ByteSize = ByteSize::Binary;
+    const DECIMAL: ByteSize = ByteSize::Decimal;
+
+    #[test]
+    fn test_word_round_trip() {
+        let word = Word::from_value(-123456, BINARY);
+        assert_eq!(word.sign, Sign::Minus);
+        assert_eq!(word.value(BINARY), -123456);
+    }
+
+    #[test]
+    fn test_word_bytes_are_base_64() {
+        let word = Word::from_value(64 * 64 + 2, BINARY);
+        assert_eq!(word.bytes, [0, 0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_word_bytes_are_base_100() {
+        let word = Word::from_value(-10203, DECIMAL);
+        assert_eq!(word.bytes, [0, 0, 1, 2, 3]);
+        assert_eq!(word.value(DECIMAL), -10203);
+    }
+
+    #[test]
+    fn test_overflow_depends_on_byte_size() {
+        assert!(Word::overflows(64i64.pow(5), BINARY));
+        assert!(!Word::overflows(64i64.pow(5), DECIMAL));
+        assert!(IndexWord::overflows(4096, BINARY));
+        assert!(!IndexWord::overflows(4096, DECIMAL));
+    }
+
+    #[test]
+    fn test_negative_zero_is_distinct() {
+        let minus_zero = Word::new(Sign::Minus, [0; 5]);
+        assert!(minus_zero.is_zero());
+        assert_eq!(minus_zero.value(BINARY), 0);
+        assert_ne!(minus_zero, Word::ZERO);
+    }
+
+    #[test]
+    fn test_field_decoding() {
+        assert_eq!(Field::decode(13).unwrap(), Field::new(1, 5).unwrap());
+        assert_eq!(Field::decode(5).unwrap(), Field::FULL);
+        assert!(Field::decode(8 * 3 + 2).is_err());
+        assert!(Field::decode(6).is_err());
+    }
+
+    #[test]
+    fn test_field_extraction() {
+        let word = Word::new(Sign::Minus, [1, 2, 3, 4, 5]);
+        assert_eq!(word.field(Field::FULL), word);
+        assert_eq!(word.field(Field::new(1, 3).unwrap()), Word::new(Sign::Plus, [0, 0, 1, 2, 3]));
+        assert_eq!(word.field(Field::new(0, 2).unwrap()), Word::new(Sign::Minus, [0, 0, 0, 1, 2]));
+        assert_eq!(word.field(Field::new(0, 0).unwrap()), Word::new(Sign::Minus, [0; 5]));
+        assert_eq!(word.field(Field::new(4, 4).unwrap()), Word::new(Sign::Plus, [0, 0, 0, 0, 4]));
+    }
+
+    #[test]
+    fn test_field_replacement() {
+        let source = Word::new(Sign::Plus, [6, 7, 8, 9, 0]);
+        let mut word = Word::new(Sign::Minus, [1, 2, 3, 4, 5]);
+        word.set_field(Field::new(1, 5).unwrap(), &source);
+        assert_eq!(word, Word::new(Sign::Minus, [6, 7, 8, 9, 0]));
+
+        let mut word = Word::new(Sign::Minus, [1, 2, 3, 4, 5]);
+        word.set_field(Field::new(5, 5).unwrap(), &source);
+        assert_eq!(word, Word::new(Sign::Minus, [1, 2, 3, 4, 0]));
+
+        let mut word = Word::new(Sign::Minus, [1, 2, 3, 4, 5]);
+        word.set_field(Field::new(2, 2).unwrap(), &source);
+        assert_eq!(word, Word::new(Sign::Minus, [1, 0, 3, 4, 5]));
+
+        let mut word = Word::new(Sign::Minus, [1, 2, 3, 4, 5]);
+        word.set_field(Field::new(0, 1).unwrap(), &source);
+        assert_eq!(word, Word::new(Sign::Plus, [0, 2, 3, 4, 5]));
+    }
+
+    #[test]
+    fn test_double_word_round_trip() {
+        for byte_size in [BINARY, DECIMAL] {
+            let magnitude = byte_size.limit(5) as u128 * 1234 + 5678;
+            let (high, low) = split_double(magnitude, byte_size);
+            let high = Word::new(Sign::Minus, high);
+            let low = Word::new(Sign::Plus, low);
+            assert_eq!(high.value(byte_size), -1234);
+            assert_eq!(low.value(byte_size), 5678);
+            assert_eq!(double_magnitude(&high, &low, byte_size), magnitude);
+        }
+    }
+
+    #[test]
+    fn test_index_word_from_word() {
+        let word = Word::from_value(-100, BINARY);
+        assert_eq!(IndexWord::from_word(&word).unwrap().value(BINARY), -100);
+        assert!(IndexWord::from_word(&Word::from_value(64 * 64 * 64, BINARY)).is_err());
+    }
+}
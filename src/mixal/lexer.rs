@@ -17,7 +17,7 @@ pub enum Token {
 
 impl Display for Token {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        return match self {
+        match self {
             Token::Instruction(x) => write!(f, "Instruction({})", x),
             Token::Register(x) => write!(f, "Register({})", x),
             Token::Number(x) => write!(f, "Number({})", x),
@@ -76,7 +76,7 @@ impl<'a> Lexer<'a> {
                     self.next_char();
                     continue;
                 },
-                _ if c.is_digit(10) => self.lex_number(),
+                _ if c.is_ascii_digit() => self.lex_number(),
                 _ if c.is_alphabetic() => self.lex_instruction(),
                 _ => {
                     let start = self.current_position();
@@ -130,7 +130,7 @@ impl<'a> Lexer<'a> {
     fn lex_number(&mut self) -> Token {
         let mut number = String::new();
         while let Some(c) = self.lookahead {
-            if c.is_digit(10) {
+            if c.is_ascii_digit() {
                 number.push(c);
                 self.next_char();
             } else {
@@ -184,7 +184,7 @@ impl<'a> Lexer<'a> {
     fn lex_dollar(&mut self) -> Token {
         self.next_char(); // consume the '$'
         match self.lookahead {
-            Some(c) if c.is_digit(10) => self.lex_register(),
+            Some(c) if c.is_ascii_digit() => self.lex_register(),
             _ => self.lex_directive(),
         }
     }
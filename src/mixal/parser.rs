@@ -13,7 +13,7 @@ impl fmt::Display for AstNode {
         match self {
             AstNode::Program(inst) => {
                 for instruction in inst {
-                    write!(f, "{}\n", instruction)?;
+                    writeln!(f, "{}", instruction)?;
                 }
             },
             AstNode::Instruction(inst) => write!(f, "{}", inst)?,
@@ -92,7 +92,7 @@ impl Parser {
                     let directive = self.parse_directive()?;
                     directives.push(directive);
                 },
-                Token::Error(ref message, _, _) => return Err("Error"),
+                Token::Error(_, _, _) => return Err("Error"),
                 _ => return Err("Expected an instruction or directive")
             }
         }
@@ -107,7 +107,7 @@ impl Parser {
         };
 
         let mut operands = Vec::new();
-        while let Some(token) = self.tokens.get(0) {
+        while let Some(token) = self.tokens.first() {
             match token {
                 Token::Register(_) | Token::Number(_) | Token::Label(_) | Token::Directive(_) | Token::ParenOpen=> {
                     let operand = self.parse_operand()?;